@@ -4,25 +4,84 @@ mod sessions;
 mod tests;
 
 use crate::install::populate_data_dir;
-use sessions::{assert_session, assert_session_ne, list_sessions};
+use sessions::{
+    assert_session, assert_session_ne, capture_pane, kill_all_sessions, kill_session,
+    list_sessions, print_session_tree, send_actions_to_session, session_exists, write_to_pane,
+};
 use std::convert::TryFrom;
+use std::io::{self, Read};
+use std::path::PathBuf;
 use std::process;
-use zellij_client::{os_input_output::get_client_os_input, start_client, ClientInfo};
+use zellij_client::{
+    os_input_output::{get_client_os_input, ClientOsApi, ClientOsInputOutput},
+    start_client, ClientInfo,
+};
 use zellij_server::{os_input_output::get_server_os_input, start_server};
 use zellij_utils::{
-    cli::{CliArgs, Command, Sessions},
+    cli::{unescape, CliArgs, Command, Sessions},
     consts::{ZELLIJ_TMP_DIR, ZELLIJ_TMP_LOG_DIR},
-    input::config::Config,
+    input::{actions::Action, command::RunCommand, config::Config, layout::SessionLayout},
+    ipc::ClientType,
     logging::*,
-    setup::{get_default_data_dir, Setup},
+    setup::{get_default_data_dir, get_saved_session_path, Setup},
     structopt::StructOpt,
 };
 
 pub fn main() {
     let opts = CliArgs::from_args();
 
-    if let Some(Command::Sessions(Sessions::ListSessions)) = opts.command {
-        list_sessions();
+    if let Some(Command::Sessions(Sessions::ListSessions { json })) = opts.command {
+        list_sessions(json);
+    } else if let Some(Command::Sessions(Sessions::KillSession { ref target_session })) =
+        opts.command
+    {
+        kill_session(target_session);
+    } else if let Some(Command::Sessions(Sessions::KillAllSessions)) = opts.command {
+        kill_all_sessions();
+    } else if let Some(Command::Action {
+        ref session,
+        ref action,
+    }) = opts.command
+    {
+        send_actions_to_session(session.clone(), action.actions());
+    } else if let Some(Command::Run {
+        direction,
+        cwd,
+        name,
+        command,
+    }) = opts.command.clone()
+    {
+        // the session may run in another directory than this command
+        let current_dir = std::env::current_dir().unwrap_or_default();
+        let run_command = RunCommand {
+            command: command.first().map(PathBuf::from),
+            args: command.iter().skip(1).cloned().collect(),
+            cwd: Some(cwd.map_or_else(|| current_dir.clone(), |cwd| current_dir.join(cwd))),
+            ..Default::default()
+        };
+        send_actions_to_session(None, vec![Action::Run(run_command, direction, name)]);
+    } else if let Some(Command::WriteToPane { pane, ref chars }) = opts.command {
+        let bytes = match chars {
+            Some(chars) => unescape(chars).into_bytes(),
+            None => {
+                let mut bytes = Vec::new();
+                if let Err(e) = io::stdin().read_to_end(&mut bytes) {
+                    eprintln!("Could not read standard input: {}", e);
+                    process::exit(1);
+                }
+                bytes
+            }
+        };
+        write_to_pane(pane, bytes);
+    } else if let Some(Command::QuerySession { ref session }) = opts.command {
+        print_session_tree(session.clone());
+    } else if let Some(Command::CapturePane {
+        pane,
+        scrollback,
+        ansi,
+    }) = opts.command
+    {
+        capture_pane(pane, scrollback, ansi);
     } else if let Some(Command::Setup(ref setup)) = opts.command {
         Setup::from_cli(setup, &opts).expect("Failed to print to stdout");
     }
@@ -56,33 +115,81 @@ pub fn main() {
         if let Some(Command::Sessions(Sessions::Attach {
             session_name,
             force,
+            read_only,
+            resurrect,
         })) = opts.command.clone()
         {
-            assert_session(&session_name);
-            start_client(
-                Box::new(os_input),
-                opts,
-                config,
-                ClientInfo::Attach(session_name, force),
-            );
+            if resurrect && !session_exists(&session_name) {
+                // start the session again, from where it was last saved
+                let data_dir = opts.data_dir.clone().unwrap_or_else(get_default_data_dir);
+                let saved_session_path = get_saved_session_path(&data_dir, &session_name);
+                if !saved_session_path.exists() {
+                    eprintln!("No saved session named {:?} found.", session_name);
+                    process::exit(1);
+                }
+                let opts = CliArgs {
+                    session: Some(session_name),
+                    layout: None,
+                    layout_path: Some(saved_session_path),
+                    command: None,
+                    ..opts
+                };
+                start_new_session(os_input, opts, config);
+            } else {
+                assert_session(&session_name);
+                start_client(
+                    Box::new(os_input),
+                    opts,
+                    config,
+                    ClientInfo::Attach(
+                        session_name,
+                        force,
+                        if read_only {
+                            ClientType::Reader
+                        } else {
+                            ClientType::Writer
+                        },
+                    ),
+                );
+            }
         } else {
-            let session_name = opts
-                .session
-                .clone()
-                .unwrap_or_else(|| names::Generator::default().next().unwrap());
-            assert_session_ne(&session_name);
-            // Determine and initialize the data directory
-            let data_dir = opts.data_dir.clone().unwrap_or_else(get_default_data_dir);
+            start_new_session(os_input, opts, config);
+        }
+    }
+}
 
-            #[cfg(not(disable_automatic_asset_installation))]
-            populate_data_dir(&data_dir);
+fn start_new_session(os_input: ClientOsInputOutput, opts: CliArgs, config: Config) {
+    let session_name = opts
+        .session
+        .clone()
+        .unwrap_or_else(|| names::Generator::default().next().unwrap());
+    assert_session_ne(&session_name);
+    // Determine and initialize the data directory
+    let data_dir = opts.data_dir.clone().unwrap_or_else(get_default_data_dir);
 
-            start_client(
-                Box::new(os_input),
-                opts,
-                config,
-                ClientInfo::New(session_name),
-            );
-        }
+    #[cfg(not(disable_automatic_asset_installation))]
+    populate_data_dir(&data_dir);
+
+    let layout_variables = opts.layout_variables();
+    let layout = opts
+        .layout
+        .as_ref()
+        .map(|p| SessionLayout::from_dir(p, &data_dir, &layout_variables))
+        .or_else(|| {
+            opts.layout_path
+                .as_ref()
+                .map(|p| SessionLayout::new(p, &layout_variables))
+        });
+    let screen_size = os_input.get_terminal_size_using_fd(0);
+    if let Some(Err(e)) = layout.map(|l| l.and_then(|l| l.check_fits(&screen_size))) {
+        eprintln!("There was an error in the layout file:\n{}", e);
+        process::exit(1);
     }
+
+    start_client(
+        Box::new(os_input),
+        opts,
+        config,
+        ClientInfo::New(session_name),
+    );
 }
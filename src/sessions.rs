@@ -1,11 +1,47 @@
+use std::fmt::{self, Display, Formatter};
 use std::os::unix::fs::FileTypeExt;
-use std::{fs, io, process};
+use std::path::Path;
+use std::sync::mpsc;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use std::{fs, io, process, thread};
 use zellij_utils::{
-    consts::ZELLIJ_SOCK_DIR,
+    consts::{VERSION, ZELLIJ_SOCK_DIR},
+    input::actions::Action,
     interprocess::local_socket::LocalSocketStream,
-    ipc::{ClientToServerMsg, IpcSenderWithContext},
+    ipc::{
+        handshake_with_server, ClientToServerMsg, ExitReason, IpcSenderWithContext,
+        ServerToClientMsg, SessionInfo,
+    },
+    serde_json,
 };
 
+/// Why a session could not be talked to.
+enum SessionError {
+    Connect(String, io::Error),
+    Incompatible(ExitReason),
+    // The session went away before answering
+    Disconnected(String),
+    // The session did not answer in time
+    Unresponsive(String),
+}
+
+/// How long a session has to answer a query before it is deemed hung.
+const QUERY_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// How long a killed session has to go away before the kill is deemed failed.
+const KILL_TIMEOUT: Duration = Duration::from_secs(5);
+
+impl Display for SessionError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Connect(name, e) => write!(f, "Could not connect to session {:?}: {}", name, e),
+            Self::Incompatible(reason) => write!(f, "{}", reason),
+            Self::Disconnected(name) => write!(f, "Session {:?} closed the connection", name),
+            Self::Unresponsive(name) => write!(f, "Session {:?} is not answering", name),
+        }
+    }
+}
+
 fn get_sessions() -> Result<Vec<String>, io::ErrorKind> {
     match fs::read_dir(&*ZELLIJ_SOCK_DIR) {
         Ok(files) => {
@@ -29,21 +65,221 @@ fn get_sessions() -> Result<Vec<String>, io::ErrorKind> {
     }
 }
 
-pub(crate) fn list_sessions() {
+/// Returns the sessions started by other versions of zellij, whose sockets are in the sibling
+/// directories of ours. They are not connected to, as older versions would misread anything
+/// sent to them.
+fn get_sessions_of_other_versions() -> Vec<SessionInfo> {
+    let version_dirs = match ZELLIJ_SOCK_DIR.parent().map(fs::read_dir) {
+        Some(Ok(version_dirs)) => version_dirs,
+        _ => return Vec::new(),
+    };
+    let mut sessions = Vec::new();
+    for version_dir in version_dirs.filter_map(Result::ok) {
+        let version = version_dir.file_name().to_string_lossy().to_string();
+        if version == VERSION {
+            continue;
+        }
+        if let Ok(files) = fs::read_dir(version_dir.path()) {
+            for file in files.filter_map(Result::ok) {
+                if file.file_type().map_or(false, |t| t.is_socket()) {
+                    sessions.push(incompatible_session_info(&file.path(), version.clone()));
+                }
+            }
+        }
+    }
+    sessions
+}
+
+/// Returns what can be known of a session without talking to it.
+fn incompatible_session_info(socket_path: &Path, version: String) -> SessionInfo {
+    let created = fs::metadata(socket_path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .map(|since_epoch| since_epoch.as_secs())
+        .unwrap_or_default();
+    SessionInfo {
+        name: socket_path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        created,
+        connected_clients: 0,
+        tabs: Vec::new(),
+        version,
+        compatible: false,
+        reachable: false,
+    }
+}
+
+/// Returns what can be known of a session of our version that could not be asked about itself.
+fn unreachable_session_info(socket_path: &Path) -> SessionInfo {
+    SessionInfo {
+        compatible: true,
+        ..incompatible_session_info(socket_path, VERSION.to_string())
+    }
+}
+
+/// Connects to the named session, if it runs a version of zellij we can talk to.
+fn connect_to_session(name: &str) -> Result<IpcSenderWithContext<ClientToServerMsg>, SessionError> {
+    connect_to_session_in(&ZELLIJ_SOCK_DIR, name)
+}
+
+fn connect_to_session_in(
+    sock_dir: &Path,
+    name: &str,
+) -> Result<IpcSenderWithContext<ClientToServerMsg>, SessionError> {
+    let mut stream = LocalSocketStream::connect(&*sock_dir.join(name))
+        .map_err(|e| SessionError::Connect(name.to_string(), e))?;
+    handshake_with_server(&mut stream).map_err(SessionError::Incompatible)?;
+    Ok(IpcSenderWithContext::new(stream))
+}
+
+/// Sends a query to the named session, and returns its answer, unless the session hangs.
+fn query_session(name: &str, query: ClientToServerMsg) -> Result<ServerToClientMsg, SessionError> {
+    query_session_in(&ZELLIJ_SOCK_DIR, name, query)
+}
+
+fn query_session_in(
+    sock_dir: &Path,
+    name: &str,
+    query: ClientToServerMsg,
+) -> Result<ServerToClientMsg, SessionError> {
+    let (answer_sender, answer_receiver) = mpsc::channel();
+    let sock_dir = sock_dir.to_path_buf();
+    let session_name = name.to_string();
+    // a hung session would block forever whoever waits for it, so this thread does
+    thread::spawn(move || {
+        let answer = connect_to_session_in(&sock_dir, &session_name).and_then(|mut sender| {
+            let answer = ask_session(&session_name, &mut sender, query);
+            let _ = sender.send(ClientToServerMsg::ClientExited);
+            answer
+        });
+        let _ = answer_sender.send(answer);
+    });
+    answer_receiver
+        .recv_timeout(QUERY_TIMEOUT)
+        .unwrap_or_else(|_| Err(SessionError::Unresponsive(name.to_string())))
+}
+
+/// Sends a query on a connection to the named session, and waits for its answer.
+fn ask_session(
+    name: &str,
+    sender: &mut IpcSenderWithContext<ClientToServerMsg>,
+    query: ClientToServerMsg,
+) -> Result<ServerToClientMsg, SessionError> {
+    let mut receiver = sender.get_receiver();
+    sender
+        .send(query)
+        .map_err(|e| SessionError::Connect(name.to_string(), e))?;
+    receiver
+        .recv()
+        .map(|(answer, _)| answer)
+        .map_err(|_| SessionError::Disconnected(name.to_string()))
+}
+
+/// Returns the given session name, or else that of the session this runs in.
+fn session_name_or_current(session_name: Option<String>) -> String {
+    match session_name.or_else(|| std::env::var("ZELLIJ_SESSION_NAME").ok()) {
+        Some(session_name) => session_name,
+        None => {
+            eprintln!("Not inside a zellij session: ZELLIJ_SESSION_NAME is not set. Use --session to name one.");
+            process::exit(1);
+        }
+    }
+}
+
+fn get_session_info(name: &str) -> Option<SessionInfo> {
+    get_session_info_in(&ZELLIJ_SOCK_DIR, name)
+}
+
+fn get_session_info_in(sock_dir: &Path, name: &str) -> Option<SessionInfo> {
+    match query_session_in(sock_dir, name, ClientToServerMsg::QuerySessionInfo) {
+        Ok(ServerToClientMsg::SessionInfo(session_info)) => Some(session_info),
+        Err(SessionError::Incompatible(ExitReason::IncompatibleVersion(version))) => {
+            Some(incompatible_session_info(
+                &sock_dir.join(name),
+                version.unwrap_or_else(|| "unknown".into()),
+            ))
+        }
+        Err(SessionError::Incompatible(ExitReason::MissingCapabilities(version, _))) => {
+            Some(incompatible_session_info(&sock_dir.join(name), version))
+        }
+        Err(SessionError::Incompatible(_)) => None,
+        // hung, or dead without removing its socket
+        Err(_) => Some(unreachable_session_info(&sock_dir.join(name))),
+        Ok(_) => unreachable!(),
+    }
+}
+
+fn format_age(created: u64) -> String {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|since_epoch| since_epoch.as_secs())
+        .unwrap_or_default();
+    let age = now.saturating_sub(created);
+    match age {
+        0..=59 => format!("{}s", age),
+        60..=3599 => format!("{}m", age / 60),
+        3600..=86399 => format!("{}h", age / 3600),
+        _ => format!("{}d", age / 86400),
+    }
+}
+
+/// The sessions as `zellij list-sessions --json` prints them.
+fn sessions_json(session_infos: &[SessionInfo]) -> String {
+    serde_json::to_string_pretty(session_infos).unwrap()
+}
+
+pub(crate) fn list_sessions(json: bool) {
     let exit_code = match get_sessions() {
         Ok(sessions) => {
-            if sessions.is_empty() {
+            let session_infos: Vec<SessionInfo> = sessions
+                .iter()
+                .filter_map(|session| get_session_info(session))
+                .chain(get_sessions_of_other_versions())
+                .collect();
+            if json {
+                println!("{}", sessions_json(&session_infos));
+            } else if session_infos.is_empty() {
                 println!("No active zellij sessions found.");
             } else {
                 let curr_session =
                     std::env::var("ZELLIJ_SESSION_NAME").unwrap_or_else(|_| "".into());
-                sessions.iter().for_each(|session| {
-                    let suffix = if curr_session == *session {
+                session_infos.iter().for_each(|session_info| {
+                    let suffix = if curr_session == session_info.name {
                         " (current)"
                     } else {
                         ""
                     };
-                    println!("{}{}", session, suffix);
+                    if !session_info.compatible {
+                        println!(
+                            "{}{} [created {} ago] [incompatible: zellij {}]",
+                            session_info.name,
+                            suffix,
+                            format_age(session_info.created),
+                            session_info.version,
+                        );
+                        return;
+                    }
+                    if !session_info.reachable {
+                        println!(
+                            "{}{} [created {} ago] [unreachable]",
+                            session_info.name,
+                            suffix,
+                            format_age(session_info.created),
+                        );
+                        return;
+                    }
+                    println!(
+                        "{}{} [created {} ago] [{} attached] [{} tabs: {}]",
+                        session_info.name,
+                        suffix,
+                        format_age(session_info.created),
+                        session_info.connected_clients,
+                        session_info.tabs.len(),
+                        session_info.tabs.join(", "),
+                    );
                 })
             }
             0
@@ -56,14 +292,188 @@ pub(crate) fn list_sessions() {
     process::exit(exit_code);
 }
 
+fn kill_session_impl(name: &str) -> Result<(), SessionError> {
+    let mut sender = connect_to_session(name)?;
+    sender
+        .send(ClientToServerMsg::KillSession)
+        .map_err(|e| SessionError::Connect(name.to_string(), e))?;
+    let _ = sender.send(ClientToServerMsg::ClientExited);
+    // the session is gone once it removed its socket
+    let socket_path = ZELLIJ_SOCK_DIR.join(name);
+    let killed_at = Instant::now();
+    while socket_path.exists() {
+        if killed_at.elapsed() > KILL_TIMEOUT {
+            return Err(SessionError::Unresponsive(name.to_string()));
+        }
+        thread::sleep(Duration::from_millis(50));
+    }
+    Ok(())
+}
+
+pub(crate) fn kill_session(name: &str) {
+    assert_session(name);
+    let exit_code = match kill_session_impl(name) {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("{}", e);
+            1
+        }
+    };
+    process::exit(exit_code);
+}
+
+pub(crate) fn kill_all_sessions() {
+    let exit_code = match get_sessions() {
+        Ok(sessions) => {
+            if sessions.is_empty() {
+                println!("No active zellij sessions found.");
+            } else {
+                for session in &sessions {
+                    match kill_session_impl(session) {
+                        Ok(()) => println!("Killed session {:?}.", session),
+                        Err(e) => eprintln!("{}", e),
+                    }
+                }
+            }
+            0
+        }
+        Err(e) => {
+            eprintln!("Error occured: {:?}", e);
+            1
+        }
+    };
+    process::exit(exit_code);
+}
+
+/// Sends the actions to the named session, or to the current one.
+pub(crate) fn send_actions_to_session(session_name: Option<String>, actions: Vec<Action>) {
+    let session_name = session_name_or_current(session_name);
+    let exit_code = match send_actions(&session_name, actions) {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("{}", e);
+            1
+        }
+    };
+    process::exit(exit_code);
+}
+
+fn send_actions(name: &str, actions: Vec<Action>) -> Result<(), String> {
+    let mut sender = connect_to_session(name).map_err(|e| e.to_string())?;
+    let sent = actions.into_iter().try_for_each(|action| match action {
+        // wait to tell whether the layout was saved
+        Action::DumpLayout(path) => {
+            match ask_session(name, &mut sender, ClientToServerMsg::DumpLayout(path)) {
+                Ok(ServerToClientMsg::ActionResult(result)) => {
+                    result.map_err(|e| format!("Could not save the layout: {}", e))
+                }
+                Ok(_) => unreachable!(),
+                Err(e) => Err(e.to_string()),
+            }
+        }
+        // and whether the session took its new name
+        Action::RenameSession(new_name) => {
+            match ask_session(
+                name,
+                &mut sender,
+                ClientToServerMsg::RenameSession(new_name),
+            ) {
+                Ok(ServerToClientMsg::ActionResult(result)) => {
+                    result.map_err(|e| format!("Could not rename the session: {}", e))
+                }
+                Ok(_) => unreachable!(),
+                Err(e) => Err(e.to_string()),
+            }
+        }
+        action => sender
+            .send(ClientToServerMsg::Action(action))
+            .map_err(|e| SessionError::Connect(name.to_string(), e).to_string()),
+    });
+    let _ = sender.send(ClientToServerMsg::ClientExited);
+    sent
+}
+
+pub(crate) fn write_to_pane(pane_id: u32, bytes: Vec<u8>) {
+    let session_name = session_name_or_current(None);
+    let exit_code = match query_session(
+        &session_name,
+        ClientToServerMsg::WriteToPane(pane_id, bytes),
+    ) {
+        Ok(ServerToClientMsg::ActionResult(Ok(()))) => 0,
+        Ok(ServerToClientMsg::ActionResult(Err(e))) => {
+            eprintln!("Could not write to pane {}: {}", pane_id, e);
+            1
+        }
+        Ok(_) => unreachable!(),
+        Err(e) => {
+            eprintln!("{}", e);
+            1
+        }
+    };
+    process::exit(exit_code);
+}
+
+pub(crate) fn print_session_tree(session_name: Option<String>) {
+    let session_name = session_name_or_current(session_name);
+    let exit_code = match query_session(&session_name, ClientToServerMsg::QuerySessionTree) {
+        Ok(ServerToClientMsg::SessionTree(session_tree)) => {
+            println!("{}", serde_json::to_string_pretty(&session_tree).unwrap());
+            0
+        }
+        Ok(_) => unreachable!(),
+        Err(e) => {
+            eprintln!("{}", e);
+            1
+        }
+    };
+    process::exit(exit_code);
+}
+
+pub(crate) fn capture_pane(pane_id: Option<u32>, with_scrollback: bool, with_ansi: bool) {
+    let session_name = session_name_or_current(None);
+    let query = ClientToServerMsg::CapturePane(pane_id, with_scrollback, with_ansi);
+    let exit_code = match query_session(&session_name, query) {
+        Ok(ServerToClientMsg::PaneContents(Some(contents))) => {
+            print!("{}", contents);
+            0
+        }
+        Ok(ServerToClientMsg::PaneContents(None)) => {
+            match pane_id {
+                Some(pane_id) => eprintln!("No pane with id {} found.", pane_id),
+                None => eprintln!("No focused pane found."),
+            }
+            1
+        }
+        Ok(_) => unreachable!(),
+        Err(e) => {
+            eprintln!("{}", e);
+            1
+        }
+    };
+    process::exit(exit_code);
+}
+
+pub(crate) fn session_exists(name: &str) -> bool {
+    matches!(get_sessions(), Ok(sessions) if sessions.iter().any(|s| s == name))
+}
+
 pub(crate) fn assert_session(name: &str) {
     let exit_code = match get_sessions() {
         Ok(sessions) => {
             if sessions.iter().any(|s| s == name) {
                 return;
             }
-            println!("No session named {:?} found.", name);
-            0
+            match get_sessions_of_other_versions()
+                .iter()
+                .find(|session| session.name == name)
+            {
+                Some(session) => eprintln!(
+                    "{}",
+                    ExitReason::IncompatibleVersion(Some(session.version.clone()))
+                ),
+                None => eprintln!("No session named {:?} found.", name),
+            }
+            1
         }
         Err(e) => {
             eprintln!("Error occured: {:?}", e);
@@ -93,10 +503,8 @@ pub(crate) fn assert_session_ne(name: &str) {
 fn assert_socket(name: &str) -> bool {
     let path = &*ZELLIJ_SOCK_DIR.join(name);
     match LocalSocketStream::connect(path) {
-        Ok(stream) => {
-            IpcSenderWithContext::new(stream).send(ClientToServerMsg::ClientExited);
-            true
-        }
+        // the server lets the connection go when no handshake comes
+        Ok(_) => true,
         Err(e) => {
             if e.kind() == io::ErrorKind::ConnectionRefused {
                 drop(fs::remove_file(path));
@@ -107,3 +515,7 @@ fn assert_socket(name: &str) -> bool {
         }
     }
 }
+
+#[cfg(test)]
+#[path = "./unit/sessions_tests.rs"]
+mod sessions_tests;
@@ -1,7 +1,6 @@
 use std::collections::{HashMap, VecDeque};
 use std::io::Write;
 use std::os::unix::io::RawFd;
-use std::path::PathBuf;
 use std::sync::{mpsc, Arc, Condvar, Mutex};
 use std::time::{Duration, Instant};
 
@@ -16,8 +15,9 @@ use zellij_utils::{
     async_std,
     channels::{ChannelWithContext, SenderType, SenderWithContext},
     errors::ErrorContext,
+    input::command::{RunCommand, TerminalAction},
     interprocess::local_socket::LocalSocketStream,
-    ipc::{ClientToServerMsg, ServerToClientMsg},
+    ipc::{ClientId, ClientToServerMsg, ExitReason, ServerToClientMsg},
     pane_size::PositionAndSize,
     shared::default_palette,
 };
@@ -77,6 +77,7 @@ pub struct FakeInputOutput {
     input_to_add: Arc<Mutex<Option<Vec<[u8; 10]>>>>,
     stdin_commands: Arc<Mutex<VecDeque<Vec<u8>>>>,
     stdin_writes: Arc<Mutex<HashMap<RawFd, Vec<u8>>>>,
+    spawned_terminal_actions: Arc<Mutex<Vec<Option<TerminalAction>>>>,
     pub stdout_writer: FakeStdoutWriter, // stdout_writer.output is already an arc/mutex
     io_events: Arc<Mutex<Vec<IoEvent>>>,
     win_sizes: Arc<Mutex<HashMap<RawFd, PositionAndSize>>>,
@@ -105,6 +106,7 @@ impl FakeInputOutput {
         FakeInputOutput {
             read_buffers: Arc::new(Mutex::new(HashMap::new())),
             stdin_writes: Arc::new(Mutex::new(HashMap::new())),
+            spawned_terminal_actions: Arc::new(Mutex::new(Vec::new())),
             input_to_add: Arc::new(Mutex::new(None)),
             stdin_commands: Arc::new(Mutex::new(VecDeque::new())),
             stdout_writer,
@@ -137,6 +139,18 @@ impl FakeInputOutput {
     pub fn add_sigwinch_event(&mut self, new_position_and_size: PositionAndSize) {
         self.sigwinch_event = Some(new_position_and_size);
     }
+    pub fn stdin_writes_to(&self, fd: RawFd) -> Vec<u8> {
+        self.stdin_writes
+            .lock()
+            .unwrap()
+            .get(&fd)
+            .cloned()
+            .unwrap_or_default()
+    }
+    /// What each terminal spawned so far was asked to run, in the order they were spawned.
+    pub fn spawned_terminal_actions(&self) -> Vec<Option<TerminalAction>> {
+        self.spawned_terminal_actions.lock().unwrap().clone()
+    }
 }
 
 impl ClientOsApi for FakeInputOutput {
@@ -222,7 +236,9 @@ impl ClientOsApi for FakeInputOutput {
             sigwinch_cb();
         }
     }
-    fn connect_to_server(&self, _path: &std::path::Path) {}
+    fn connect_to_server(&self, _path: &std::path::Path) -> Result<(), ExitReason> {
+        Ok(())
+    }
     fn load_palette(&self) -> Palette {
         default_palette()
     }
@@ -270,7 +286,11 @@ impl ServerOsApi for FakeInputOutput {
             .unwrap()
             .push(IoEvent::SetTerminalSizeUsingFd(pid, cols, rows));
     }
-    fn spawn_terminal(&self, _file_to_open: Option<PathBuf>) -> (RawFd, Pid) {
+    fn spawn_terminal(&self, terminal_action: Option<TerminalAction>) -> (RawFd, Pid) {
+        self.spawned_terminal_actions
+            .lock()
+            .unwrap()
+            .push(terminal_action);
         let next_terminal_id = self.stdin_writes.lock().unwrap().keys().len() as RawFd + 1;
         self.add_terminal(next_terminal_id);
         (
@@ -322,21 +342,40 @@ impl ServerOsApi for FakeInputOutput {
         self.io_events.lock().unwrap().push(IoEvent::Kill(pid));
         Ok(())
     }
-    fn recv_from_client(&self) -> (ClientToServerMsg, ErrorContext) {
+    fn recv_from_client(&self) -> Option<(ClientToServerMsg, ErrorContext)> {
         self.receive_instructions_from_client
             .lock()
             .unwrap()
             .recv()
-            .unwrap()
+            .ok()
     }
-    fn send_to_client(&self, msg: ServerToClientMsg) {
+    fn send_to_client(&self, _client_id: ClientId, msg: ServerToClientMsg) {
         self.send_instructions_to_client.send(msg).unwrap();
     }
-    fn add_client_sender(&self) {}
-    fn remove_client_sender(&self) {}
+    fn add_client_sender(&self, _client_id: ClientId) {}
+    fn remove_client_sender(&self, _client_id: ClientId) {}
     fn send_to_temp_client(&self, _msg: ServerToClientMsg) {}
     fn update_receiver(&mut self, _stream: LocalSocketStream) {}
     fn load_palette(&self) -> Palette {
         default_palette()
     }
+    fn get_foreground_command(&self, fd: RawFd) -> Option<RunCommand> {
+        // the command the terminal was spawned with, as they are numbered in that order
+        match self
+            .spawned_terminal_actions
+            .lock()
+            .unwrap()
+            .get((fd as usize).checked_sub(1)?)
+        {
+            Some(Some(TerminalAction::RunCommand(run_command))) => Some(run_command.clone()),
+            _ => None,
+        }
+    }
+    fn terminal_id(&self, fd: RawFd) -> Option<u32> {
+        // fake terminals are numbered without reuse already
+        Some(fd as u32)
+    }
+    fn terminal_fd(&self, terminal_id: u32) -> Option<RawFd> {
+        Some(terminal_id as RawFd)
+    }
 }
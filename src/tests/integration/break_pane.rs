@@ -0,0 +1,139 @@
+use ::insta::assert_snapshot;
+use std::path::PathBuf;
+
+use crate::tests::fakes::FakeInputOutput;
+use crate::tests::start;
+use crate::tests::utils::{get_next_to_last_snapshot, get_output_frame_snapshots};
+use crate::CliArgs;
+
+use crate::tests::utils::commands::{
+    BREAK_PANE_IN_PANE_MODE, JOIN_PANE_TO_TAB_1_IN_PANE_MODE, PANE_MODE, QUIT,
+    SPLIT_RIGHT_IN_PANE_MODE,
+};
+use zellij_utils::input::config::Config;
+use zellij_utils::pane_size::PositionAndSize;
+
+fn get_fake_os_input(fake_win_size: &PositionAndSize) -> FakeInputOutput {
+    FakeInputOutput::new(*fake_win_size)
+}
+
+fn snapshot_before_quit(
+    fake_input_output: &FakeInputOutput,
+    fake_win_size: &PositionAndSize,
+) -> String {
+    let output_frames = fake_input_output
+        .stdout_writer
+        .output_frames
+        .lock()
+        .unwrap();
+    let snapshots = get_output_frame_snapshots(&output_frames, fake_win_size);
+    get_next_to_last_snapshot(snapshots).expect("could not find snapshot")
+}
+
+#[test]
+pub fn break_pane_to_new_tab() {
+    let fake_win_size = PositionAndSize {
+        columns: 121,
+        rows: 20,
+        x: 0,
+        y: 0,
+        ..Default::default()
+    };
+    let mut fake_input_output = get_fake_os_input(&fake_win_size);
+    fake_input_output.add_terminal_input(&[
+        &PANE_MODE,
+        &SPLIT_RIGHT_IN_PANE_MODE,
+        &BREAK_PANE_IN_PANE_MODE,
+        &QUIT,
+    ]);
+    start(
+        Box::new(fake_input_output.clone()),
+        CliArgs::default(),
+        Box::new(fake_input_output.clone()),
+        Config::default(),
+    );
+
+    let snapshot_before_quit = snapshot_before_quit(&fake_input_output, &fake_win_size);
+    assert_snapshot!(snapshot_before_quit);
+}
+
+#[test]
+pub fn break_pane_to_new_tab_with_layout_template() {
+    let fake_win_size = PositionAndSize {
+        columns: 121,
+        rows: 20,
+        x: 0,
+        y: 0,
+        ..Default::default()
+    };
+    let mut fake_input_output = get_fake_os_input(&fake_win_size);
+    fake_input_output.add_terminal_input(&[&PANE_MODE, &BREAK_PANE_IN_PANE_MODE, &QUIT]);
+    let mut opts = CliArgs::default();
+    opts.layout_path = Some(PathBuf::from(
+        "src/tests/fixtures/layouts/three-panes-with-nesting.yaml",
+    ));
+    start(
+        Box::new(fake_input_output.clone()),
+        opts,
+        Box::new(fake_input_output.clone()),
+        Config::default(),
+    );
+
+    let snapshot_before_quit = snapshot_before_quit(&fake_input_output, &fake_win_size);
+    assert_snapshot!(snapshot_before_quit);
+}
+
+#[test]
+pub fn cannot_break_pane_that_is_alone_in_its_tab() {
+    let fake_win_size = PositionAndSize {
+        columns: 121,
+        rows: 20,
+        x: 0,
+        y: 0,
+        ..Default::default()
+    };
+    let mut fake_input_output = get_fake_os_input(&fake_win_size);
+    fake_input_output.add_terminal_input(&[
+        &PANE_MODE,
+        &BREAK_PANE_IN_PANE_MODE,
+        &SPLIT_RIGHT_IN_PANE_MODE,
+        &QUIT,
+    ]);
+    start(
+        Box::new(fake_input_output.clone()),
+        CliArgs::default(),
+        Box::new(fake_input_output.clone()),
+        Config::default(),
+    );
+
+    let snapshot_before_quit = snapshot_before_quit(&fake_input_output, &fake_win_size);
+    assert_snapshot!(snapshot_before_quit);
+}
+
+#[test]
+pub fn join_pane_to_tab() {
+    let fake_win_size = PositionAndSize {
+        columns: 121,
+        rows: 20,
+        x: 0,
+        y: 0,
+        ..Default::default()
+    };
+    let mut fake_input_output = get_fake_os_input(&fake_win_size);
+    fake_input_output.add_terminal_input(&[
+        &PANE_MODE,
+        &SPLIT_RIGHT_IN_PANE_MODE,
+        &BREAK_PANE_IN_PANE_MODE,
+        &JOIN_PANE_TO_TAB_1_IN_PANE_MODE,
+        &QUIT,
+    ]);
+    start(
+        Box::new(fake_input_output.clone()),
+        CliArgs::default(),
+        Box::new(fake_input_output.clone()),
+        Config::default(),
+    );
+
+    let snapshot_before_quit = snapshot_before_quit(&fake_input_output, &fake_win_size);
+    assert_snapshot!(snapshot_before_quit);
+}
@@ -0,0 +1,92 @@
+use crate::tests::fakes::FakeInputOutput;
+use crate::tests::start;
+use crate::CliArgs;
+
+use crate::tests::utils::commands::QUIT;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+use zellij_utils::input::config::Config;
+use zellij_utils::input::layout::SessionLayout;
+use zellij_utils::pane_size::PositionAndSize;
+
+const RUN_IN_NORMAL_MODE: [u8; 2] = [27, 114]; // alt-r, bound by the config of each test
+const DUMP_LAYOUT_IN_NORMAL_MODE: [u8; 2] = [27, 100]; // alt-d, bound by the config of each test
+
+fn get_fake_os_input(fake_win_size: &PositionAndSize) -> FakeInputOutput {
+    FakeInputOutput::new(*fake_win_size)
+}
+
+fn dump_path(test_name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("zellij-{}-{}.yaml", test_name, process::id()))
+}
+
+fn config_running(run: &str, dump_path: &Path) -> Config {
+    Config::from_yaml(&format!(
+        "
+keybinds:
+    normal:
+        - action: [Run: {},]
+          key: [ Alt: 'r',]
+        - action: [DumpLayout: {:?},]
+          key: [ Alt: 'd',]
+",
+        run, dump_path
+    ))
+    .unwrap()
+}
+
+fn dump_layout_after_running(run: &str, dump_path: &Path) -> SessionLayout {
+    let fake_win_size = PositionAndSize {
+        columns: 121,
+        rows: 20,
+        x: 0,
+        y: 0,
+        ..Default::default()
+    };
+    let mut fake_input_output = get_fake_os_input(&fake_win_size);
+    fake_input_output.add_terminal_input(&[
+        &RUN_IN_NORMAL_MODE,
+        &DUMP_LAYOUT_IN_NORMAL_MODE,
+        &QUIT,
+    ]);
+    start(
+        Box::new(fake_input_output.clone()),
+        CliArgs::default(),
+        Box::new(fake_input_output.clone()),
+        config_running(run, dump_path),
+    );
+    let dumped_layout = fs::read_to_string(dump_path).expect("the layout was not dumped");
+    drop(fs::remove_file(dump_path));
+    SessionLayout::from_yaml_with_variables(&dumped_layout, &HashMap::new())
+        .expect("the dumped layout cannot be loaded")
+}
+
+#[test]
+pub fn dumped_layout_recreates_the_panes_and_their_commands() {
+    let dump_path = dump_path("dumped-layout-recreates-the-panes");
+    let session_layout =
+        dump_layout_after_running("[{command: htop, args: [-d, '10']}, Right, ~]", &dump_path);
+
+    assert_eq!(session_layout.tabs.len(), 1);
+    let panes = &session_layout.tabs[0].parts;
+    assert_eq!(panes.len(), 2);
+    // the shell pane on the left, the command in the focused pane on the right
+    assert_eq!(panes[0].run.command, None);
+    assert_eq!(panes[1].run.command, Some(PathBuf::from("htop")));
+    assert_eq!(panes[1].run.args, vec!["-d", "10"]);
+    assert_eq!(panes[1].focus, Some(true));
+}
+
+#[test]
+pub fn braces_in_a_dumped_layout_are_not_taken_for_variables() {
+    let dump_path = dump_path("braces-in-a-dumped-layout");
+    let session_layout = dump_layout_after_running(
+        "[{command: docker, args: [ps, --format, '{{.Names}}']}, Right, ~]",
+        &dump_path,
+    );
+
+    let panes = &session_layout.tabs[0].parts;
+    assert_eq!(panes[1].run.args, vec!["ps", "--format", "{{.Names}}"]);
+}
@@ -0,0 +1,120 @@
+use ::insta::assert_snapshot;
+use zellij_utils::pane_size::PositionAndSize;
+
+use crate::tests::fakes::FakeInputOutput;
+use crate::tests::start;
+use crate::tests::utils::{get_next_to_last_snapshot, get_output_frame_snapshots};
+use crate::CliArgs;
+
+use crate::tests::utils::commands::{
+    NEXT_LAYOUT_IN_PANE_MODE, PANE_MODE, QUIT, SPLIT_DOWN_IN_PANE_MODE, SPLIT_RIGHT_IN_PANE_MODE,
+};
+use zellij_utils::input::config::Config;
+
+const SELECT_TILED_LAYOUT_IN_PANE_MODE: [u8; 1] = [84]; // T, bound by the config of the test
+
+fn get_fake_os_input(fake_win_size: &PositionAndSize) -> FakeInputOutput {
+    FakeInputOutput::new(*fake_win_size)
+}
+
+fn fake_win_size() -> PositionAndSize {
+    PositionAndSize {
+        columns: 121,
+        rows: 20,
+        x: 0,
+        y: 0,
+        ..Default::default()
+    }
+}
+
+fn snapshot_before_quit(config: Config, input: &[&[u8]]) -> String {
+    let fake_win_size = fake_win_size();
+    let mut fake_input_output = get_fake_os_input(&fake_win_size);
+    fake_input_output.add_terminal_input(input);
+    start(
+        Box::new(fake_input_output.clone()),
+        CliArgs::default(),
+        Box::new(fake_input_output.clone()),
+        config,
+    );
+
+    let output_frames = fake_input_output
+        .stdout_writer
+        .output_frames
+        .lock()
+        .unwrap();
+    let snapshots = get_output_frame_snapshots(&output_frames, &fake_win_size);
+    get_next_to_last_snapshot(snapshots).expect("could not find snapshot")
+}
+
+#[test]
+pub fn next_layout_starts_with_panes_side_by_side() {
+    let snapshot_before_quit = snapshot_before_quit(
+        Config::default(),
+        &[
+            &PANE_MODE,
+            &SPLIT_DOWN_IN_PANE_MODE,
+            &SPLIT_DOWN_IN_PANE_MODE,
+            &NEXT_LAYOUT_IN_PANE_MODE,
+            &QUIT,
+        ],
+    );
+    assert_snapshot!(snapshot_before_quit);
+}
+
+#[test]
+pub fn next_layout_wraps_around_after_the_last_preset() {
+    let first_preset = snapshot_before_quit(
+        Config::default(),
+        &[
+            &PANE_MODE,
+            &SPLIT_DOWN_IN_PANE_MODE,
+            &SPLIT_DOWN_IN_PANE_MODE,
+            &NEXT_LAYOUT_IN_PANE_MODE,
+            &QUIT,
+        ],
+    );
+    // the five presets, and then the first one again
+    let snapshot_before_quit = snapshot_before_quit(
+        Config::default(),
+        &[
+            &PANE_MODE,
+            &SPLIT_DOWN_IN_PANE_MODE,
+            &SPLIT_DOWN_IN_PANE_MODE,
+            &NEXT_LAYOUT_IN_PANE_MODE,
+            &NEXT_LAYOUT_IN_PANE_MODE,
+            &NEXT_LAYOUT_IN_PANE_MODE,
+            &NEXT_LAYOUT_IN_PANE_MODE,
+            &NEXT_LAYOUT_IN_PANE_MODE,
+            &NEXT_LAYOUT_IN_PANE_MODE,
+            &QUIT,
+        ],
+    );
+    assert_eq!(snapshot_before_quit, first_preset);
+    assert_snapshot!(snapshot_before_quit);
+}
+
+#[test]
+pub fn select_tiled_layout() {
+    let config = Config::from_yaml(
+        "
+keybinds:
+    pane:
+        - action: [SelectLayout: Tiled,]
+          key: [ Char: 'T',]
+",
+    )
+    .unwrap();
+    let snapshot_before_quit = snapshot_before_quit(
+        config,
+        &[
+            &PANE_MODE,
+            &SPLIT_RIGHT_IN_PANE_MODE,
+            &SPLIT_RIGHT_IN_PANE_MODE,
+            &SPLIT_RIGHT_IN_PANE_MODE,
+            &SELECT_TILED_LAYOUT_IN_PANE_MODE,
+            &QUIT,
+        ],
+    );
+    assert_snapshot!(snapshot_before_quit);
+}
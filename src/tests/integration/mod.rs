@@ -1,15 +1,25 @@
 pub mod basic;
+pub mod break_pane;
 pub mod close_pane;
 pub mod compatibility;
+pub mod dump_layout;
+pub mod equalize_panes;
+pub mod layout_presets;
 pub mod layouts;
 pub mod move_focus_down;
 pub mod move_focus_left;
 pub mod move_focus_right;
 pub mod move_focus_up;
+pub mod move_pane;
+pub mod move_tab;
 pub mod resize_down;
 pub mod resize_left;
 pub mod resize_right;
 pub mod resize_up;
+pub mod rotate_panes;
+pub mod run;
+pub mod swap_pane;
+pub mod sync_groups;
 pub mod tabs;
 pub mod terminal_window_resize;
 pub mod toggle_fullscreen;
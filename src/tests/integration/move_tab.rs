@@ -0,0 +1,100 @@
+use crate::tests::fakes::FakeInputOutput;
+use crate::tests::start;
+use crate::tests::utils::{get_next_to_last_snapshot, get_output_frame_snapshots};
+use crate::CliArgs;
+use ::insta::assert_snapshot;
+
+use crate::tests::utils::commands::{
+    GO_TO_TAB_2_IN_TAB_MODE, MOVE_TAB_LEFT_IN_TAB_MODE, NEW_TAB_IN_TAB_MODE, PANE_MODE, QUIT,
+    SPLIT_DOWN_IN_PANE_MODE, TAB_MODE,
+};
+use zellij_utils::input::config::Config;
+use zellij_utils::pane_size::PositionAndSize;
+
+fn get_fake_os_input(fake_win_size: &PositionAndSize) -> FakeInputOutput {
+    FakeInputOutput::new(*fake_win_size)
+}
+
+fn snapshot_before_quit(
+    fake_input_output: &FakeInputOutput,
+    fake_win_size: &PositionAndSize,
+) -> String {
+    let output_frames = fake_input_output
+        .stdout_writer
+        .output_frames
+        .lock()
+        .unwrap();
+    let snapshots = get_output_frame_snapshots(&output_frames, fake_win_size);
+    get_next_to_last_snapshot(snapshots).expect("could not find snapshot")
+}
+
+#[test]
+pub fn move_tab_left() {
+    // the first tab is split, the second one is moved in front of it, so that the split tab is
+    // the one at the second position
+    let fake_win_size = PositionAndSize {
+        columns: 121,
+        rows: 20,
+        x: 0,
+        y: 0,
+        ..Default::default()
+    };
+    let mut fake_input_output = get_fake_os_input(&fake_win_size);
+    fake_input_output.add_terminal_input(&[
+        &PANE_MODE,
+        &SPLIT_DOWN_IN_PANE_MODE,
+        &TAB_MODE,
+        &NEW_TAB_IN_TAB_MODE,
+        &MOVE_TAB_LEFT_IN_TAB_MODE,
+        &GO_TO_TAB_2_IN_TAB_MODE,
+        &QUIT,
+    ]);
+    start(
+        Box::new(fake_input_output.clone()),
+        CliArgs::default(),
+        Box::new(fake_input_output.clone()),
+        Config::default(),
+    );
+
+    let snapshot_before_quit = snapshot_before_quit(&fake_input_output, &fake_win_size);
+    assert_snapshot!(snapshot_before_quit);
+}
+
+#[test]
+pub fn move_tab_to_position_zero_moves_it_to_the_first_position() {
+    let fake_win_size = PositionAndSize {
+        columns: 121,
+        rows: 20,
+        x: 0,
+        y: 0,
+        ..Default::default()
+    };
+    let config = Config::from_yaml(
+        "
+keybinds:
+    tab:
+        - action: [MoveTabToPosition: 0,]
+          key: [ Char: '0',]
+",
+    )
+    .unwrap();
+    let mut fake_input_output = get_fake_os_input(&fake_win_size);
+    fake_input_output.add_terminal_input(&[
+        &PANE_MODE,
+        &SPLIT_DOWN_IN_PANE_MODE,
+        &TAB_MODE,
+        &NEW_TAB_IN_TAB_MODE,
+        &[48], // 0
+        &GO_TO_TAB_2_IN_TAB_MODE,
+        &QUIT,
+    ]);
+    start(
+        Box::new(fake_input_output.clone()),
+        CliArgs::default(),
+        Box::new(fake_input_output.clone()),
+        config,
+    );
+
+    let snapshot_before_quit = snapshot_before_quit(&fake_input_output, &fake_win_size);
+    assert_snapshot!(snapshot_before_quit);
+}
@@ -0,0 +1,80 @@
+use crate::tests::fakes::FakeInputOutput;
+use crate::tests::start;
+use crate::tests::utils::{get_next_to_last_snapshot, get_output_frame_snapshots};
+use crate::CliArgs;
+use ::insta::assert_snapshot;
+
+use crate::tests::utils::commands::{
+    MOVE_FOCUS_LEFT_IN_PANE_MODE, PANE_MODE, QUIT, ROTATE_PANES_BACKWARDS_IN_PANE_MODE,
+    ROTATE_PANES_IN_PANE_MODE, SPLIT_RIGHT_IN_PANE_MODE,
+};
+use zellij_utils::input::config::Config;
+use zellij_utils::pane_size::PositionAndSize;
+
+fn get_fake_os_input(fake_win_size: &PositionAndSize) -> FakeInputOutput {
+    FakeInputOutput::new(*fake_win_size)
+}
+
+fn snapshot_before_quit(fake_win_size: &PositionAndSize, input: &[&[u8]]) -> String {
+    let mut fake_input_output = get_fake_os_input(fake_win_size);
+    fake_input_output.add_terminal_input(input);
+    start(
+        Box::new(fake_input_output.clone()),
+        CliArgs::default(),
+        Box::new(fake_input_output.clone()),
+        Config::default(),
+    );
+
+    let output_frames = fake_input_output
+        .stdout_writer
+        .output_frames
+        .lock()
+        .unwrap();
+    let snapshots = get_output_frame_snapshots(&output_frames, fake_win_size);
+    get_next_to_last_snapshot(snapshots).expect("could not find snapshot")
+}
+
+#[test]
+pub fn rotate_panes() {
+    // the focused pane on the right takes the place of the pane before it
+    let fake_win_size = PositionAndSize {
+        columns: 121,
+        rows: 20,
+        x: 0,
+        y: 0,
+        ..Default::default()
+    };
+    let snapshot_before_quit = snapshot_before_quit(
+        &fake_win_size,
+        &[
+            &PANE_MODE,
+            &SPLIT_RIGHT_IN_PANE_MODE,
+            &ROTATE_PANES_IN_PANE_MODE,
+            &QUIT,
+        ],
+    );
+    assert_snapshot!(snapshot_before_quit);
+}
+
+#[test]
+pub fn rotate_panes_backwards() {
+    // the focused pane on the left takes the place of the pane after it
+    let fake_win_size = PositionAndSize {
+        columns: 121,
+        rows: 20,
+        x: 0,
+        y: 0,
+        ..Default::default()
+    };
+    let snapshot_before_quit = snapshot_before_quit(
+        &fake_win_size,
+        &[
+            &PANE_MODE,
+            &SPLIT_RIGHT_IN_PANE_MODE,
+            &MOVE_FOCUS_LEFT_IN_PANE_MODE,
+            &ROTATE_PANES_BACKWARDS_IN_PANE_MODE,
+            &QUIT,
+        ],
+    );
+    assert_snapshot!(snapshot_before_quit);
+}
@@ -0,0 +1,111 @@
+use crate::tests::fakes::FakeInputOutput;
+use crate::tests::start;
+use crate::tests::utils::{get_next_to_last_snapshot, get_output_frame_snapshots};
+use crate::CliArgs;
+use ::insta::assert_snapshot;
+
+use crate::tests::utils::commands::QUIT;
+use std::path::PathBuf;
+use zellij_utils::input::command::TerminalAction;
+use zellij_utils::input::config::Config;
+use zellij_utils::pane_size::PositionAndSize;
+
+const RUN_IN_NORMAL_MODE: [u8; 2] = [27, 114]; // alt-r, bound by the config of each test
+const TYPE_X: [u8; 1] = [120]; // x, typed in normal mode
+
+fn get_fake_os_input(fake_win_size: &PositionAndSize) -> FakeInputOutput {
+    FakeInputOutput::new(*fake_win_size)
+}
+
+fn fake_win_size() -> PositionAndSize {
+    PositionAndSize {
+        columns: 121,
+        rows: 20,
+        x: 0,
+        y: 0,
+        ..Default::default()
+    }
+}
+
+fn config_running(run: &str) -> Config {
+    Config::from_yaml(&format!(
+        "
+keybinds:
+    normal:
+        - action: [Run: {},]
+          key: [ Alt: 'r',]
+",
+        run
+    ))
+    .unwrap()
+}
+
+fn snapshot_before_quit(
+    fake_input_output: &FakeInputOutput,
+    fake_win_size: &PositionAndSize,
+) -> String {
+    let output_frames = fake_input_output
+        .stdout_writer
+        .output_frames
+        .lock()
+        .unwrap();
+    let snapshots = get_output_frame_snapshots(&output_frames, fake_win_size);
+    get_next_to_last_snapshot(snapshots).expect("could not find snapshot")
+}
+
+fn spawned_command(fake_input_output: &FakeInputOutput) -> Option<(PathBuf, Vec<String>)> {
+    match fake_input_output.spawned_terminal_actions().last() {
+        Some(Some(TerminalAction::RunCommand(run_command))) => {
+            Some((run_command.command.clone()?, run_command.args.clone()))
+        }
+        _ => None,
+    }
+}
+
+#[test]
+pub fn run_command_in_a_new_pane_to_the_right() {
+    let fake_win_size = fake_win_size();
+    let mut fake_input_output = get_fake_os_input(&fake_win_size);
+    fake_input_output.add_terminal_input(&[&RUN_IN_NORMAL_MODE, &TYPE_X, &QUIT]);
+    start(
+        Box::new(fake_input_output.clone()),
+        CliArgs::default(),
+        Box::new(fake_input_output.clone()),
+        config_running("[{command: htop, args: [-d, '10']}, Right, monitor]"),
+    );
+
+    assert_eq!(fake_input_output.spawned_terminal_actions().len(), 2);
+    assert_eq!(
+        spawned_command(&fake_input_output),
+        Some((
+            PathBuf::from("htop"),
+            vec!["-d".to_string(), "10".to_string()]
+        ))
+    );
+    // the pane of the command is focused
+    assert!(fake_input_output.stdin_writes_to(1).is_empty());
+    assert_eq!(fake_input_output.stdin_writes_to(2), TYPE_X.to_vec());
+    let snapshot_before_quit = snapshot_before_quit(&fake_input_output, &fake_win_size);
+    assert_snapshot!(snapshot_before_quit);
+}
+
+#[test]
+pub fn run_command_without_direction_or_name() {
+    let fake_win_size = fake_win_size();
+    let mut fake_input_output = get_fake_os_input(&fake_win_size);
+    fake_input_output.add_terminal_input(&[&RUN_IN_NORMAL_MODE, &TYPE_X, &QUIT]);
+    start(
+        Box::new(fake_input_output.clone()),
+        CliArgs::default(),
+        Box::new(fake_input_output.clone()),
+        config_running("[{command: htop}, ~, ~]"),
+    );
+
+    assert_eq!(
+        spawned_command(&fake_input_output),
+        Some((PathBuf::from("htop"), Vec::new()))
+    );
+    assert_eq!(fake_input_output.stdin_writes_to(2), TYPE_X.to_vec());
+    let snapshot_before_quit = snapshot_before_quit(&fake_input_output, &fake_win_size);
+    assert_snapshot!(snapshot_before_quit);
+}
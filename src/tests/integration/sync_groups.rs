@@ -0,0 +1,122 @@
+use crate::tests::fakes::FakeInputOutput;
+use crate::tests::start;
+use crate::tests::utils::{get_next_to_last_snapshot, get_output_frame_snapshots};
+use crate::CliArgs;
+use ::insta::assert_snapshot;
+
+use crate::tests::utils::commands::{
+    ENTER, JOIN_SYNC_GROUP_IN_PANE_MODE, LEAVE_SYNC_GROUP_IN_PANE_MODE, NEW_TAB_IN_TAB_MODE,
+    PANE_MODE, QUIT, SPLIT_RIGHT_IN_PANE_MODE, TAB_MODE,
+};
+use zellij_utils::input::config::Config;
+use zellij_utils::pane_size::PositionAndSize;
+
+const TYPE_X: [u8; 1] = [120]; // x, typed in normal mode
+
+fn get_fake_os_input(fake_win_size: &PositionAndSize) -> FakeInputOutput {
+    FakeInputOutput::new(*fake_win_size)
+}
+
+fn fake_win_size() -> PositionAndSize {
+    PositionAndSize {
+        columns: 121,
+        rows: 20,
+        x: 0,
+        y: 0,
+        ..Default::default()
+    }
+}
+
+fn snapshot_before_quit(
+    fake_input_output: &FakeInputOutput,
+    fake_win_size: &PositionAndSize,
+) -> String {
+    let output_frames = fake_input_output
+        .stdout_writer
+        .output_frames
+        .lock()
+        .unwrap();
+    let snapshots = get_output_frame_snapshots(&output_frames, fake_win_size);
+    get_next_to_last_snapshot(snapshots).expect("could not find snapshot")
+}
+
+#[test]
+pub fn input_is_sent_to_all_panes_of_a_sync_group() {
+    let fake_win_size = fake_win_size();
+    let mut fake_input_output = get_fake_os_input(&fake_win_size);
+    fake_input_output.add_terminal_input(&[
+        &PANE_MODE,
+        &JOIN_SYNC_GROUP_IN_PANE_MODE,
+        &SPLIT_RIGHT_IN_PANE_MODE,
+        &JOIN_SYNC_GROUP_IN_PANE_MODE,
+        &ENTER,
+        &TYPE_X,
+        &QUIT,
+    ]);
+    start(
+        Box::new(fake_input_output.clone()),
+        CliArgs::default(),
+        Box::new(fake_input_output.clone()),
+        Config::default(),
+    );
+
+    assert_eq!(fake_input_output.stdin_writes_to(1), TYPE_X.to_vec());
+    assert_eq!(fake_input_output.stdin_writes_to(2), TYPE_X.to_vec());
+    let snapshot_before_quit = snapshot_before_quit(&fake_input_output, &fake_win_size);
+    assert_snapshot!(snapshot_before_quit);
+}
+
+#[test]
+pub fn a_pane_that_left_its_sync_group_gets_only_its_own_input() {
+    let fake_win_size = fake_win_size();
+    let mut fake_input_output = get_fake_os_input(&fake_win_size);
+    fake_input_output.add_terminal_input(&[
+        &PANE_MODE,
+        &JOIN_SYNC_GROUP_IN_PANE_MODE,
+        &SPLIT_RIGHT_IN_PANE_MODE,
+        &JOIN_SYNC_GROUP_IN_PANE_MODE,
+        &LEAVE_SYNC_GROUP_IN_PANE_MODE,
+        &ENTER,
+        &TYPE_X,
+        &QUIT,
+    ]);
+    start(
+        Box::new(fake_input_output.clone()),
+        CliArgs::default(),
+        Box::new(fake_input_output.clone()),
+        Config::default(),
+    );
+
+    assert!(fake_input_output.stdin_writes_to(1).is_empty());
+    assert_eq!(fake_input_output.stdin_writes_to(2), TYPE_X.to_vec());
+    let snapshot_before_quit = snapshot_before_quit(&fake_input_output, &fake_win_size);
+    assert_snapshot!(snapshot_before_quit);
+}
+
+#[test]
+pub fn sync_groups_span_tabs() {
+    let fake_win_size = fake_win_size();
+    let mut fake_input_output = get_fake_os_input(&fake_win_size);
+    fake_input_output.add_terminal_input(&[
+        &PANE_MODE,
+        &JOIN_SYNC_GROUP_IN_PANE_MODE,
+        &TAB_MODE,
+        &NEW_TAB_IN_TAB_MODE,
+        &PANE_MODE,
+        &JOIN_SYNC_GROUP_IN_PANE_MODE,
+        &ENTER,
+        &TYPE_X,
+        &QUIT,
+    ]);
+    start(
+        Box::new(fake_input_output.clone()),
+        CliArgs::default(),
+        Box::new(fake_input_output.clone()),
+        Config::default(),
+    );
+
+    assert_eq!(fake_input_output.stdin_writes_to(1), TYPE_X.to_vec());
+    assert_eq!(fake_input_output.stdin_writes_to(2), TYPE_X.to_vec());
+    let snapshot_before_quit = snapshot_before_quit(&fake_input_output, &fake_win_size);
+    assert_snapshot!(snapshot_before_quit);
+}
@@ -66,6 +66,17 @@ pub mod commands {
     pub const MOVE_FOCUS_UP_IN_PANE_MODE: [u8; 1] = [107]; // k
     pub const MOVE_FOCUS_LEFT_IN_PANE_MODE: [u8; 1] = [104]; // h
     pub const MOVE_FOCUS_RIGHT_IN_PANE_MODE: [u8; 1] = [108]; // l
+    pub const SWAP_PANE_LEFT_IN_PANE_MODE: [u8; 1] = [72]; // H
+    pub const SWAP_PANE_RIGHT_IN_PANE_MODE: [u8; 1] = [76]; // L
+    pub const MOVE_PANE_LEFT_IN_PANE_MODE: [u8; 2] = [27, 104]; // alt-h
+    pub const MOVE_PANE_RIGHT_IN_PANE_MODE: [u8; 2] = [27, 108]; // alt-l
+    pub const ROTATE_PANES_IN_PANE_MODE: [u8; 1] = [111]; // o
+    pub const ROTATE_PANES_BACKWARDS_IN_PANE_MODE: [u8; 1] = [79]; // O
+    pub const BREAK_PANE_IN_PANE_MODE: [u8; 1] = [98]; // b
+    pub const JOIN_PANE_TO_TAB_1_IN_PANE_MODE: [u8; 1] = [49]; // 1
+    pub const JOIN_SYNC_GROUP_IN_PANE_MODE: [u8; 1] = [115]; // s
+    pub const LEAVE_SYNC_GROUP_IN_PANE_MODE: [u8; 1] = [83]; // S
+    pub const NEXT_LAYOUT_IN_PANE_MODE: [u8; 1] = [97]; // a
 
     pub const SCROLL_MODE: [u8; 1] = [19]; // ctrl-s
     pub const SCROLL_UP_IN_SCROLL_MODE: [u8; 1] = [107]; // k
@@ -78,12 +89,15 @@ pub mod commands {
     pub const RESIZE_UP_IN_RESIZE_MODE: [u8; 1] = [107]; // k
     pub const RESIZE_LEFT_IN_RESIZE_MODE: [u8; 1] = [104]; // h
     pub const RESIZE_RIGHT_IN_RESIZE_MODE: [u8; 1] = [108]; // l
+    pub const EQUALIZE_PANES_IN_RESIZE_MODE: [u8; 1] = [61]; // =
 
     pub const TAB_MODE: [u8; 1] = [20]; // ctrl-t
     pub const NEW_TAB_IN_TAB_MODE: [u8; 1] = [110]; // n
     pub const SWITCH_NEXT_TAB_IN_TAB_MODE: [u8; 1] = [108]; // l
     pub const SWITCH_PREV_TAB_IN_TAB_MODE: [u8; 1] = [104]; // h
     pub const CLOSE_TAB_IN_TAB_MODE: [u8; 1] = [120]; // x
+    pub const MOVE_TAB_LEFT_IN_TAB_MODE: [u8; 1] = [72]; // H
+    pub const GO_TO_TAB_2_IN_TAB_MODE: [u8; 1] = [50]; // 2
 
     pub const BRACKETED_PASTE_START: [u8; 6] = [27, 91, 50, 48, 48, 126]; // \u{1b}[200~
     pub const BRACKETED_PASTE_END: [u8; 6] = [27, 91, 50, 48, 49, 126]; // \u{1b}[201
@@ -0,0 +1,101 @@
+use super::*;
+
+use std::os::unix::net::UnixListener;
+use std::path::PathBuf;
+use zellij_utils::serde_json::Value;
+
+/// A directory of sockets for a single test, removed once it is done.
+struct SockDir(PathBuf);
+
+impl SockDir {
+    fn new(test_name: &str) -> Self {
+        let path = std::env::temp_dir().join(format!(
+            "zellij-sessions-test-{}-{}",
+            test_name,
+            process::id()
+        ));
+        fs::create_dir_all(&path).unwrap();
+        SockDir(path)
+    }
+}
+
+impl Drop for SockDir {
+    fn drop(&mut self) {
+        drop(fs::remove_dir_all(&self.0));
+    }
+}
+
+#[test]
+fn a_session_that_never_answers_times_out() {
+    let sock_dir = SockDir::new("never-answers");
+    // connections are let in, but the handshake is never answered
+    let _listener = UnixListener::bind(sock_dir.0.join("hung")).unwrap();
+    let queried_at = Instant::now();
+    let answer = query_session_in(&sock_dir.0, "hung", ClientToServerMsg::QuerySessionInfo);
+    assert!(matches!(answer, Err(SessionError::Unresponsive(name)) if name == "hung"));
+    assert!(queried_at.elapsed() >= QUERY_TIMEOUT);
+    assert!(queried_at.elapsed() < QUERY_TIMEOUT * 2);
+}
+
+#[test]
+fn a_session_that_never_answers_is_listed_as_unreachable() {
+    let sock_dir = SockDir::new("listed-as-unreachable");
+    let _listener = UnixListener::bind(sock_dir.0.join("hung")).unwrap();
+    let session_info = get_session_info_in(&sock_dir.0, "hung").unwrap();
+    assert_eq!(session_info.name, "hung");
+    assert_eq!(session_info.version, VERSION);
+    assert!(session_info.compatible);
+    assert!(!session_info.reachable);
+}
+
+#[test]
+fn a_dead_session_that_left_its_socket_is_listed_as_unreachable() {
+    let sock_dir = SockDir::new("dead-session");
+    // the socket stays, but nothing listens on it anymore
+    drop(UnixListener::bind(sock_dir.0.join("dead")).unwrap());
+    let session_info = get_session_info_in(&sock_dir.0, "dead").unwrap();
+    assert_eq!(session_info.name, "dead");
+    assert!(session_info.compatible);
+    assert!(!session_info.reachable);
+}
+
+#[test]
+fn sessions_are_listed_as_json_objects() {
+    let session_infos = vec![
+        SessionInfo {
+            name: "work".to_string(),
+            created: 1_600_000_000,
+            connected_clients: 2,
+            tabs: vec!["editor".to_string(), "logs".to_string()],
+            version: VERSION.to_string(),
+            compatible: true,
+            reachable: true,
+        },
+        incompatible_session_info(Path::new("/tmp/old"), "0.1.0".to_string()),
+    ];
+    let json: Value = serde_json::from_str(&sessions_json(&session_infos)).unwrap();
+    let sessions = json.as_array().unwrap();
+    assert_eq!(sessions.len(), 2);
+    let mut keys: Vec<&String> = sessions[0].as_object().unwrap().keys().collect();
+    keys.sort();
+    assert_eq!(
+        keys,
+        vec![
+            "compatible",
+            "connected_clients",
+            "created",
+            "name",
+            "reachable",
+            "tabs",
+            "version"
+        ]
+    );
+    assert_eq!(sessions[0]["name"], "work");
+    assert_eq!(sessions[0]["created"], 1_600_000_000);
+    assert_eq!(sessions[0]["connected_clients"], 2);
+    assert_eq!(sessions[0]["tabs"][1], "logs");
+    assert_eq!(sessions[0]["reachable"], true);
+    assert_eq!(sessions[1]["name"], "old");
+    assert_eq!(sessions[1]["version"], "0.1.0");
+    assert_eq!(sessions[1]["compatible"], false);
+}
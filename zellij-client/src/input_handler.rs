@@ -150,7 +150,9 @@ impl InputHandler {
             | Action::GoToPreviousTab
             | Action::CloseTab
             | Action::GoToTab(_)
-            | Action::MoveFocusOrTab(_) => {
+            | Action::MoveFocusOrTab(_)
+            | Action::BreakPane
+            | Action::JoinPaneToTab(_) => {
                 self.command_is_executing.blocking_input_thread();
                 self.os_input
                     .send_to_server(ClientToServerMsg::Action(action));
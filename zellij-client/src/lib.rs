@@ -20,7 +20,7 @@ use zellij_utils::{
     consts::{SESSION_NAME, ZELLIJ_IPC_PIPE},
     errors::{ClientContext, ContextType, ErrorInstruction},
     input::{actions::Action, config::Config, options::Options},
-    ipc::{ClientAttributes, ClientToServerMsg, ExitReason, ServerToClientMsg},
+    ipc::{ClientAttributes, ClientToServerMsg, ClientType, ExitReason, ServerToClientMsg},
 };
 
 /// Instructions related to the client-side application
@@ -38,6 +38,11 @@ impl From<ServerToClientMsg> for ClientInstruction {
             ServerToClientMsg::Exit(e) => ClientInstruction::Exit(e),
             ServerToClientMsg::Render(buffer) => ClientInstruction::Render(buffer),
             ServerToClientMsg::UnblockInputThread => ClientInstruction::UnblockInputThread,
+            // only sent to the connections that query the session
+            ServerToClientMsg::SessionInfo(_)
+            | ServerToClientMsg::SessionTree(_)
+            | ServerToClientMsg::PaneContents(_)
+            | ServerToClientMsg::ActionResult(_) => unreachable!(),
         }
     }
 }
@@ -78,7 +83,7 @@ fn spawn_server(socket_path: &Path) -> io::Result<()> {
 
 #[derive(Debug, Clone)]
 pub enum ClientInfo {
-    Attach(String, bool),
+    Attach(String, bool, ClientType),
     New(String),
 }
 
@@ -113,11 +118,11 @@ pub fn start_client(
 
     #[cfg(not(any(feature = "test", test)))]
     let first_msg = match info {
-        ClientInfo::Attach(name, force) => {
+        ClientInfo::Attach(name, force, client_type) => {
             SESSION_NAME.set(name).unwrap();
             std::env::set_var(&"ZELLIJ_SESSION_NAME", SESSION_NAME.get().unwrap());
 
-            ClientToServerMsg::AttachClient(client_attributes, force)
+            ClientToServerMsg::AttachClient(client_attributes, force, client_type)
         }
         ClientInfo::New(name) => {
             SESSION_NAME.set(name).unwrap();
@@ -138,7 +143,15 @@ pub fn start_client(
         ClientToServerMsg::NewClient(client_attributes, Box::new(opts), Box::new(config_options))
     };
 
-    os_input.connect_to_server(&*ZELLIJ_IPC_PIPE);
+    if let Err(reason) = os_input.connect_to_server(&*ZELLIJ_IPC_PIPE) {
+        let restore_snapshot = "\u{1b}[?1049l";
+        let _ = os_input
+            .get_stdout_writer()
+            .write(restore_snapshot.as_bytes())
+            .unwrap();
+        eprintln!("{}", reason);
+        std::process::exit(1);
+    }
     os_input.send_to_server(first_msg);
 
     let mut command_is_executing = CommandIsExecuting::new();
@@ -12,7 +12,10 @@ use std::sync::{Arc, Mutex};
 use zellij_tile::data::{Palette, PaletteColor};
 use zellij_utils::{
     errors::ErrorContext,
-    ipc::{ClientToServerMsg, IpcReceiverWithContext, IpcSenderWithContext, ServerToClientMsg},
+    ipc::{
+        handshake_with_server, ClientToServerMsg, ExitReason, IpcReceiverWithContext,
+        IpcSenderWithContext, ServerToClientMsg,
+    },
     pane_size::PositionAndSize,
     shared::default_palette,
 };
@@ -79,8 +82,9 @@ pub trait ClientOsApi: Send + Sync {
     // This should be called from the client-side router thread only.
     fn recv_from_server(&self) -> (ServerToClientMsg, ErrorContext);
     fn handle_signals(&self, sigwinch_cb: Box<dyn Fn()>, quit_cb: Box<dyn Fn()>);
-    /// Establish a connection with the server socket.
-    fn connect_to_server(&self, path: &Path);
+    /// Establish a connection with the server socket, unless the server runs a version of zellij
+    /// that this client cannot talk to.
+    fn connect_to_server(&self, path: &Path) -> Result<(), ExitReason>;
     fn load_palette(&self) -> Palette;
 }
 
@@ -117,7 +121,8 @@ impl ClientOsApi for ClientOsInputOutput {
             .unwrap()
             .as_mut()
             .unwrap()
-            .send(msg);
+            .send(msg)
+            .unwrap();
     }
     fn recv_from_server(&self) -> (ServerToClientMsg, ErrorContext) {
         self.receive_instructions_from_server
@@ -126,6 +131,7 @@ impl ClientOsApi for ClientOsInputOutput {
             .as_mut()
             .unwrap()
             .recv()
+            .unwrap()
     }
     fn handle_signals(&self, sigwinch_cb: Box<dyn Fn()>, quit_cb: Box<dyn Fn()>) {
         let mut signals = Signals::new(&[SIGWINCH, SIGTERM, SIGINT, SIGQUIT, SIGHUP]).unwrap();
@@ -142,8 +148,8 @@ impl ClientOsApi for ClientOsInputOutput {
             }
         }
     }
-    fn connect_to_server(&self, path: &Path) {
-        let socket;
+    fn connect_to_server(&self, path: &Path) -> Result<(), ExitReason> {
+        let mut socket;
         loop {
             match LocalSocketStream::connect(path) {
                 Ok(sock) => {
@@ -155,10 +161,12 @@ impl ClientOsApi for ClientOsInputOutput {
                 }
             }
         }
+        handshake_with_server(&mut socket)?;
         let sender = IpcSenderWithContext::new(socket);
         let receiver = sender.get_receiver();
         *self.send_instructions_to_server.lock().unwrap() = Some(sender);
         *self.receive_instructions_from_server.lock().unwrap() = Some(receiver);
+        Ok(())
     }
     fn load_palette(&self) -> Palette {
         let timeout = std::time::Duration::from_millis(100);
@@ -11,8 +11,10 @@ mod wasm_vm;
 
 use zellij_utils::zellij_tile;
 
+use std::collections::BTreeMap;
 use std::sync::{Arc, Mutex, RwLock};
 use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use std::{path::PathBuf, sync::mpsc};
 use wasmer::Store;
 use zellij_tile::data::{Event, InputMode, PluginCapabilities};
@@ -22,43 +24,41 @@ use crate::{
     pty::{pty_thread_main, Pty, PtyInstruction},
     screen::{screen_thread_main, ScreenInstruction},
     thread_bus::{Bus, ThreadSenders},
-    ui::layout::Layout,
     wasm_vm::{wasm_thread_main, PluginInstruction},
 };
 use route::route_thread_main;
 use zellij_utils::{
     channels::{ChannelWithContext, SenderType, SenderWithContext, SyncChannelWithContext},
     cli::CliArgs,
+    consts::VERSION,
     errors::{ContextType, ErrorInstruction, ServerContext},
-    input::{get_mode_info, options::Options},
-    ipc::{ClientAttributes, ClientToServerMsg, ExitReason, ServerToClientMsg},
-    setup::get_default_data_dir,
+    input::{get_mode_info, layout::SessionLayout, options::Options},
+    ipc::{
+        ClientAttributes, ClientId, ClientType, ExitReason, ServerToClientMsg, SessionInfo,
+        SessionTree,
+    },
+    logging::debug_log_to_file,
+    setup::{get_default_data_dir, get_saved_session_path},
 };
 
+// how often the session is saved, to be resurrected if the server dies
+const SESSION_SAVE_INTERVAL: Duration = Duration::from_secs(30);
+
 /// Instructions related to server-side application
 #[derive(Debug, Clone)]
 pub(crate) enum ServerInstruction {
-    NewClient(ClientAttributes, Box<CliArgs>, Box<Options>),
+    NewClient(ClientAttributes, Box<CliArgs>, Box<Options>, ClientId),
     Render(Option<String>),
     UnblockInputThread,
     ClientExit,
     Error(String),
-    DetachSession,
-    AttachClient(ClientAttributes, bool),
-}
-
-impl From<ClientToServerMsg> for ServerInstruction {
-    fn from(instruction: ClientToServerMsg) -> Self {
-        match instruction {
-            ClientToServerMsg::NewClient(attrs, opts, options) => {
-                ServerInstruction::NewClient(attrs, opts, options)
-            }
-            ClientToServerMsg::AttachClient(attrs, force) => {
-                ServerInstruction::AttachClient(attrs, force)
-            }
-            _ => unreachable!(),
-        }
-    }
+    DetachSession(ClientId),
+    AttachClient(ClientAttributes, bool, ClientType, ClientId),
+    TerminalResize(ClientId, PositionAndSize),
+    RemoveClient(ClientId),
+    RenameSession(String, Option<ClientId>),
+    QuerySessionInfo(ClientId),
+    QuerySessionTree(ClientId),
 }
 
 impl From<&ServerInstruction> for ServerContext {
@@ -69,8 +69,13 @@ impl From<&ServerInstruction> for ServerContext {
             ServerInstruction::UnblockInputThread => ServerContext::UnblockInputThread,
             ServerInstruction::ClientExit => ServerContext::ClientExit,
             ServerInstruction::Error(_) => ServerContext::Error,
-            ServerInstruction::DetachSession => ServerContext::DetachSession,
+            ServerInstruction::DetachSession(_) => ServerContext::DetachSession,
             ServerInstruction::AttachClient(..) => ServerContext::AttachClient,
+            ServerInstruction::TerminalResize(..) => ServerContext::TerminalResize,
+            ServerInstruction::RemoveClient(_) => ServerContext::RemoveClient,
+            ServerInstruction::RenameSession(..) => ServerContext::RenameSession,
+            ServerInstruction::QuerySessionInfo(_) => ServerContext::QuerySessionInfo,
+            ServerInstruction::QuerySessionTree(_) => ServerContext::QuerySessionTree,
         }
     }
 }
@@ -100,6 +105,62 @@ impl Drop for SessionMetaData {
     }
 }
 
+/// The clients attached to the session, the size of their terminals and whether they are
+/// read-only.
+#[derive(Default)]
+pub(crate) struct AttachedClients {
+    clients: BTreeMap<ClientId, (PositionAndSize, ClientType)>,
+}
+
+impl AttachedClients {
+    pub fn attach(&mut self, client_id: ClientId, size: PositionAndSize, client_type: ClientType) {
+        self.clients.insert(client_id, (size, client_type));
+    }
+    /// Returns whether the client was attached.
+    pub fn detach(&mut self, client_id: ClientId) -> bool {
+        self.clients.remove(&client_id).is_some()
+    }
+    /// Returns whether the client is attached.
+    pub fn resize(&mut self, client_id: ClientId, new_size: PositionAndSize) -> bool {
+        match self.clients.get_mut(&client_id) {
+            Some((size, _)) => {
+                *size = new_size;
+                true
+            }
+            None => false,
+        }
+    }
+    pub fn contains(&self, client_id: ClientId) -> bool {
+        self.clients.contains_key(&client_id)
+    }
+    pub fn ids(&self) -> impl Iterator<Item = ClientId> + '_ {
+        self.clients.keys().copied()
+    }
+    pub fn len(&self) -> usize {
+        self.clients.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.clients.is_empty()
+    }
+    /// The size that fits in the terminals of all clients that can write to the session, if any
+    /// is attached. Read-only clients watch the session at whatever size it has.
+    pub fn smallest_size(&self) -> Option<PositionAndSize> {
+        let mut sizes = self
+            .clients
+            .values()
+            .filter(|(_, client_type)| *client_type == ClientType::Writer)
+            .map(|(size, _)| size);
+        let first_size = *sizes.next()?;
+        Some(
+            sizes.fold(first_size, |smallest_size, size| PositionAndSize {
+                columns: smallest_size.columns.min(size.columns),
+                rows: smallest_size.rows.min(size.rows),
+                ..smallest_size
+            }),
+        )
+    }
+}
+
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub(crate) enum SessionState {
     Attached,
@@ -107,7 +168,7 @@ pub(crate) enum SessionState {
     Uninitialized,
 }
 
-pub fn start_server(os_input: Box<dyn ServerOsApi>, socket_path: PathBuf) {
+pub fn start_server(os_input: Box<dyn ServerOsApi>, mut socket_path: PathBuf) {
     #[cfg(not(any(feature = "test", test)))]
     daemonize::Daemonize::new()
         .working_directory(std::env::current_dir().unwrap())
@@ -144,7 +205,7 @@ pub fn start_server(os_input: Box<dyn ServerOsApi>, socket_path: PathBuf) {
                 let to_server = to_server.clone();
                 let session_state = session_state.clone();
 
-                move || route_thread_main(session_data, session_state, os_input, to_server)
+                move || route_thread_main(session_data, session_state, os_input, to_server, 0)
             })
             .unwrap(),
     );
@@ -153,7 +214,8 @@ pub fn start_server(os_input: Box<dyn ServerOsApi>, socket_path: PathBuf) {
         .name("server_listener".to_string())
         .spawn({
             use zellij_utils::{
-                interprocess::local_socket::LocalSocketListener, shared::set_permissions,
+                interprocess::local_socket::LocalSocketListener, ipc::handshake_with_client,
+                shared::set_permissions,
             };
 
             let os_input = os_input.clone();
@@ -163,31 +225,42 @@ pub fn start_server(os_input: Box<dyn ServerOsApi>, socket_path: PathBuf) {
             let socket_path = socket_path.clone();
             let thread_handles = thread_handles.clone();
             move || {
+                let mut next_client_id: ClientId = 0;
                 drop(std::fs::remove_file(&socket_path));
                 let listener = LocalSocketListener::bind(&*socket_path).unwrap();
                 set_permissions(&socket_path).unwrap();
                 for stream in listener.incoming() {
                     match stream {
-                        Ok(stream) => {
-                            let mut os_input = os_input.clone();
-                            os_input.update_receiver(stream);
+                        Ok(mut stream) => {
+                            let os_input = os_input.clone();
                             let session_data = session_data.clone();
                             let session_state = session_state.clone();
                             let to_server = to_server.clone();
+                            // ids are never reused, so that an answer meant for a client
+                            // that is gone cannot reach another one
+                            let client_id = next_client_id;
+                            next_client_id += 1;
                             thread_handles.lock().unwrap().push(
                                 thread::Builder::new()
                                     .name("server_router".to_string())
                                     .spawn({
                                         let session_data = session_data.clone();
-                                        let os_input = os_input.clone();
+                                        let mut os_input = os_input.clone();
                                         let to_server = to_server.clone();
 
                                         move || {
+                                            // clients of other versions are let go before they
+                                            // send anything we would misread
+                                            if !handshake_with_client(&mut stream) {
+                                                return;
+                                            }
+                                            os_input.update_receiver(stream);
                                             route_thread_main(
                                                 session_data,
                                                 session_state,
                                                 os_input,
                                                 to_server,
+                                                client_id,
                                             )
                                         }
                                     })
@@ -202,11 +275,32 @@ pub fn start_server(os_input: Box<dyn ServerOsApi>, socket_path: PathBuf) {
             }
         });
 
+    let mut attached_clients = AttachedClients::default();
+    let session_name = Arc::new(RwLock::new(
+        socket_path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default(),
+    ));
+    // where the session is saved, known once it started
+    let mut data_dir: Option<PathBuf> = None;
+    // whether the session ended normally, rather than because of an error
+    let mut ended_normally = false;
+    let created = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|since_epoch| since_epoch.as_secs())
+        .unwrap_or_default();
     loop {
         let (instruction, mut err_ctx) = server_receiver.recv().unwrap();
         err_ctx.add_call(ContextType::IPCServer((&instruction).into()));
         match instruction {
-            ServerInstruction::NewClient(client_attributes, opts, config_options) => {
+            ServerInstruction::NewClient(client_attributes, opts, config_options, client_id) => {
+                attached_clients.attach(
+                    client_id,
+                    client_attributes.position_and_size,
+                    ClientType::Writer,
+                );
+                data_dir = Some(opts.data_dir.clone().unwrap_or_else(get_default_data_dir));
                 let session = init_session(
                     os_input.clone(),
                     opts,
@@ -214,6 +308,7 @@ pub fn start_server(os_input: Box<dyn ServerOsApi>, socket_path: PathBuf) {
                     to_server.clone(),
                     client_attributes,
                     session_state.clone(),
+                    session_name.clone(),
                 );
                 *session_data.write().unwrap() = Some(session);
                 *session_state.write().unwrap() = SessionState::Attached;
@@ -223,17 +318,26 @@ pub fn start_server(os_input: Box<dyn ServerOsApi>, socket_path: PathBuf) {
                     .as_ref()
                     .unwrap()
                     .senders
-                    .send_to_pty(PtyInstruction::NewTab)
+                    .send_to_pty(PtyInstruction::OpenInitialTabs)
                     .unwrap();
             }
-            ServerInstruction::AttachClient(attrs, _) => {
+            ServerInstruction::AttachClient(attrs, force, client_type, client_id) => {
+                if force {
+                    // the other clients are detached
+                    for other_client_id in attached_clients.ids() {
+                        os_input.send_to_client(
+                            other_client_id,
+                            ServerToClientMsg::Exit(ExitReason::ForceDetached),
+                        );
+                        os_input.remove_client_sender(other_client_id);
+                    }
+                    attached_clients = AttachedClients::default();
+                }
+                attached_clients.attach(client_id, attrs.position_and_size, client_type);
                 *session_state.write().unwrap() = SessionState::Attached;
                 let rlock = session_data.read().unwrap();
                 let session_data = rlock.as_ref().unwrap();
-                session_data
-                    .senders
-                    .send_to_screen(ScreenInstruction::TerminalResize(attrs.position_and_size))
-                    .unwrap();
+                resize_to_smallest_client(session_data, &attached_clients);
                 let mode_info =
                     get_mode_info(InputMode::Normal, attrs.palette, session_data.capabilities);
                 session_data
@@ -248,37 +352,168 @@ pub fn start_server(os_input: Box<dyn ServerOsApi>, socket_path: PathBuf) {
                     ))
                     .unwrap();
             }
+            ServerInstruction::TerminalResize(client_id, new_size) => {
+                if attached_clients.resize(client_id, new_size) {
+                    if let Some(session_data) = session_data.read().unwrap().as_ref() {
+                        resize_to_smallest_client(session_data, &attached_clients);
+                    }
+                }
+            }
             ServerInstruction::UnblockInputThread => {
                 if *session_state.read().unwrap() == SessionState::Attached {
-                    os_input.send_to_client(ServerToClientMsg::UnblockInputThread);
+                    // unblocking a client that is not blocked does nothing
+                    send_to_clients(
+                        &*os_input,
+                        &attached_clients,
+                        ServerToClientMsg::UnblockInputThread,
+                    );
                 }
             }
             ServerInstruction::ClientExit => {
                 *session_data.write().unwrap() = None;
-                os_input.send_to_client(ServerToClientMsg::Exit(ExitReason::Normal));
+                send_to_clients(
+                    &*os_input,
+                    &attached_clients,
+                    ServerToClientMsg::Exit(ExitReason::Normal),
+                );
+                ended_normally = true;
                 break;
             }
-            ServerInstruction::DetachSession => {
-                *session_state.write().unwrap() = SessionState::Detached;
-                os_input.send_to_client(ServerToClientMsg::Exit(ExitReason::Normal));
-                os_input.remove_client_sender();
+            ServerInstruction::DetachSession(client_id) => {
+                os_input.send_to_client(client_id, ServerToClientMsg::Exit(ExitReason::Normal));
+                detach_client(
+                    &*os_input,
+                    &session_data,
+                    &session_state,
+                    &mut attached_clients,
+                    client_id,
+                );
+            }
+            ServerInstruction::RemoveClient(client_id) => {
+                if attached_clients.contains(client_id) {
+                    detach_client(
+                        &*os_input,
+                        &session_data,
+                        &session_state,
+                        &mut attached_clients,
+                        client_id,
+                    );
+                }
+            }
+            ServerInstruction::RenameSession(new_name, client_id) => {
+                let new_socket_path = socket_path.with_file_name(&new_name);
+                let renamed = if new_name.is_empty() || new_name.contains('/') {
+                    Err(format!("{:?} is not a valid session name", new_name))
+                } else if new_socket_path.exists() {
+                    Err(format!("a session named {:?} already exists", new_name))
+                } else {
+                    std::fs::rename(&socket_path, &new_socket_path)
+                        .map_err(|e| format!("could not rename the session: {}", e))
+                };
+                if renamed.is_ok() {
+                    // the listener keeps accepting connections on the moved socket
+                    socket_path = new_socket_path;
+                    // panes opened from now on see the new name
+                    std::env::set_var(&"ZELLIJ_SESSION_NAME", &new_name);
+                    if let Some(data_dir) = data_dir.as_ref() {
+                        // it is saved under its new name from now on
+                        drop(std::fs::rename(
+                            get_saved_session_path(data_dir, &session_name.read().unwrap()),
+                            get_saved_session_path(data_dir, &new_name),
+                        ));
+                    }
+                    *session_name.write().unwrap() = new_name.clone();
+                    if let Some(session_data) = session_data.read().unwrap().as_ref() {
+                        session_data
+                            .senders
+                            .send_to_plugin(PluginInstruction::Update(
+                                None,
+                                Event::SessionRename(new_name),
+                            ))
+                            .unwrap();
+                    }
+                }
+                match client_id {
+                    Some(client_id) => {
+                        os_input.send_to_client(client_id, ServerToClientMsg::ActionResult(renamed))
+                    }
+                    None => {
+                        if let Err(e) = renamed {
+                            let _ = debug_log_to_file(format!("cannot rename session: {}", e));
+                        }
+                    }
+                }
+            }
+            ServerInstruction::QuerySessionInfo(client_id) => {
+                let session_info = SessionInfo {
+                    name: session_name.read().unwrap().clone(),
+                    created,
+                    connected_clients: attached_clients.len(),
+                    tabs: Vec::new(),
+                    version: VERSION.to_string(),
+                    compatible: true,
+                    reachable: true,
+                };
+                match session_data.read().unwrap().as_ref() {
+                    // the screen fills in the tabs, and answers
+                    Some(session_data) => session_data
+                        .senders
+                        .send_to_screen(ScreenInstruction::QuerySessionInfo(
+                            session_info,
+                            client_id,
+                        ))
+                        .unwrap(),
+                    None => os_input
+                        .send_to_client(client_id, ServerToClientMsg::SessionInfo(session_info)),
+                }
+            }
+            ServerInstruction::QuerySessionTree(client_id) => {
+                let session_tree = SessionTree {
+                    name: session_name.read().unwrap().clone(),
+                    tabs: Vec::new(),
+                };
+                match session_data.read().unwrap().as_ref() {
+                    // the screen fills in the tabs, and answers
+                    Some(session_data) => session_data
+                        .senders
+                        .send_to_screen(ScreenInstruction::QuerySessionTree(
+                            session_tree,
+                            client_id,
+                        ))
+                        .unwrap(),
+                    None => os_input
+                        .send_to_client(client_id, ServerToClientMsg::SessionTree(session_tree)),
+                }
             }
             ServerInstruction::Render(output) => {
                 if *session_state.read().unwrap() == SessionState::Attached {
                     // Here output is of the type Option<String> sent by screen thread.
-                    // If `Some(_)`- unwrap it and forward it to the client to render.
+                    // If `Some(_)`- unwrap it and forward it to the clients to render.
                     // If `None`- Send an exit instruction. This is the case when the user closes last Tab/Pane.
                     if let Some(op) = output {
-                        os_input.send_to_client(ServerToClientMsg::Render(op));
+                        send_to_clients(
+                            &*os_input,
+                            &attached_clients,
+                            ServerToClientMsg::Render(op),
+                        );
                     } else {
-                        os_input.send_to_client(ServerToClientMsg::Exit(ExitReason::Normal));
+                        send_to_clients(
+                            &*os_input,
+                            &attached_clients,
+                            ServerToClientMsg::Exit(ExitReason::Normal),
+                        );
+                        ended_normally = true;
                         break;
                     }
                 }
             }
             ServerInstruction::Error(backtrace) => {
                 if *session_state.read().unwrap() == SessionState::Attached {
-                    os_input.send_to_client(ServerToClientMsg::Exit(ExitReason::Error(backtrace)));
+                    send_to_clients(
+                        &*os_input,
+                        &attached_clients,
+                        ServerToClientMsg::Exit(ExitReason::Error(backtrace)),
+                    );
                 }
                 break;
             }
@@ -291,6 +526,48 @@ pub fn start_server(os_input: Box<dyn ServerOsApi>, socket_path: PathBuf) {
         .for_each(|h| drop(h.join()));
     #[cfg(not(any(feature = "test", test)))]
     drop(std::fs::remove_file(&socket_path));
+    // a session that ended normally is not resurrected, but one that crashed can be
+    if let (true, Some(data_dir)) = (ended_normally, data_dir) {
+        if cfg!(not(any(feature = "test", test))) {
+            drop(std::fs::remove_file(get_saved_session_path(
+                &data_dir,
+                &session_name.read().unwrap(),
+            )));
+        }
+    }
+}
+
+fn send_to_clients(os_input: &dyn ServerOsApi, clients: &AttachedClients, msg: ServerToClientMsg) {
+    for client_id in clients.ids() {
+        os_input.send_to_client(client_id, msg.clone());
+    }
+}
+
+/// Sizes the screen to fit the terminals of all attached clients.
+fn resize_to_smallest_client(session_data: &SessionMetaData, clients: &AttachedClients) {
+    if let Some(smallest_size) = clients.smallest_size() {
+        session_data
+            .senders
+            .send_to_screen(ScreenInstruction::TerminalResize(smallest_size))
+            .unwrap();
+    }
+}
+
+fn detach_client(
+    os_input: &dyn ServerOsApi,
+    session_data: &RwLock<Option<SessionMetaData>>,
+    session_state: &RwLock<SessionState>,
+    clients: &mut AttachedClients,
+    client_id: ClientId,
+) {
+    os_input.remove_client_sender(client_id);
+    clients.detach(client_id);
+    if clients.is_empty() {
+        *session_state.write().unwrap() = SessionState::Detached;
+    } else if let Some(session_data) = session_data.read().unwrap().as_ref() {
+        // the remaining clients might have room for a bigger screen
+        resize_to_smallest_client(session_data, clients);
+    }
 }
 
 fn init_session(
@@ -300,6 +577,7 @@ fn init_session(
     to_server: SenderWithContext<ServerInstruction>,
     client_attributes: ClientAttributes,
     session_state: Arc<RwLock<SessionState>>,
+    session_name: Arc<RwLock<String>>,
 ) -> SessionMetaData {
     let (to_screen, screen_receiver): ChannelWithContext<ScreenInstruction> = mpsc::channel();
     let to_screen = SenderWithContext::new(SenderType::Sender(to_screen));
@@ -321,13 +599,41 @@ fn init_session(
     let default_layout = Some(PathBuf::from("default"));
     #[cfg(any(feature = "test", test))]
     let default_layout = None;
+    let layout_variables = opts.layout_variables();
     let layout_path = opts.layout_path;
     let maybe_layout = opts
         .layout
         .as_ref()
-        .map(|p| Layout::from_dir(&p, &data_dir))
-        .or_else(|| layout_path.map(|p| Layout::new(&p)))
-        .or_else(|| default_layout.map(|p| Layout::from_dir(&p, &data_dir)));
+        .map(|p| SessionLayout::from_dir(&p, &data_dir, &layout_variables))
+        .or_else(|| layout_path.map(|p| SessionLayout::new(&p, &layout_variables)))
+        .or_else(|| {
+            default_layout
+                .as_ref()
+                .map(|p| SessionLayout::from_dir(p, &data_dir, &layout_variables))
+        })
+        .and_then(|layout| match layout {
+            Ok(layout) => Some(layout),
+            Err(e) => {
+                // the client checks the layout before starting the session, so this should not
+                // happen - but rather than crash, start without one
+                let _ = debug_log_to_file(format!("could not load layout: {}", e));
+                None
+            }
+        })
+        .map(|mut layout| {
+            if layout.template.is_none() {
+                // tabs opened after those of the layout get the default layout instead
+                layout.template = default_layout
+                    .as_ref()
+                    .and_then(|p| SessionLayout::from_dir(p, &data_dir, &layout_variables).ok())
+                    .and_then(|default_layout| default_layout.template);
+            }
+            layout
+        });
+
+    let layout_template = maybe_layout
+        .as_ref()
+        .and_then(|layout| layout.template.clone());
 
     let pty_thread = thread::Builder::new()
         .name("pty".to_string())
@@ -342,6 +648,7 @@ fn init_session(
                     Some(os_input.clone()),
                 ),
                 opts.debug,
+                config_options.hold_on_exit,
             );
 
             move || pty_thread_main(pty, maybe_layout)
@@ -368,6 +675,7 @@ fn init_session(
                     client_attributes,
                     config_options,
                     session_state,
+                    layout_template,
                 );
             }
         })
@@ -385,10 +693,36 @@ fn init_session(
                 None,
             );
             let store = Store::default();
+            let data_dir = data_dir.clone();
 
             move || wasm_thread_main(plugin_bus, store, data_dir)
         })
         .unwrap();
+
+    // Don't save sessions in tests, but do everywhere else
+    if cfg!(not(any(feature = "test", test))) {
+        let _ = thread::Builder::new()
+            .name("session_saver".to_string())
+            .spawn({
+                let to_screen = to_screen.clone();
+                move || loop {
+                    thread::sleep(SESSION_SAVE_INTERVAL);
+                    // the session might have been renamed since the last save
+                    let saved_session_path =
+                        get_saved_session_path(&data_dir, &session_name.read().unwrap());
+                    if let Some(sessions_dir) = saved_session_path.parent() {
+                        let _ = std::fs::create_dir_all(sessions_dir);
+                    }
+                    // this fails once the screen thread has exited
+                    if to_screen
+                        .send(ScreenInstruction::DumpLayout(saved_session_path, None))
+                        .is_err()
+                    {
+                        break;
+                    }
+                }
+            });
+    }
     SessionMetaData {
         senders: ThreadSenders {
             to_screen: Some(to_screen),
@@ -402,3 +736,7 @@ fn init_session(
         wasm_thread: Some(wasm_thread),
     }
 }
+
+#[cfg(test)]
+#[path = "./unit/lib_tests.rs"]
+mod lib_tests;
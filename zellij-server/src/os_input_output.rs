@@ -1,7 +1,11 @@
+use std::collections::HashMap;
 use std::env;
+use std::fs;
+use std::io::{self, Write};
 use std::os::unix::io::RawFd;
-use std::path::PathBuf;
-use std::process::{Child, Command};
+use std::os::unix::process::ExitStatusExt;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, ExitStatus};
 use std::sync::{Arc, Mutex};
 
 use zellij_utils::{async_std, interprocess, libc, nix, signal_hook, zellij_tile};
@@ -18,8 +22,9 @@ use signal_hook::consts::*;
 use zellij_tile::data::Palette;
 use zellij_utils::{
     errors::ErrorContext,
+    input::command::{RunCommand, TerminalAction},
     ipc::{
-        ClientToServerMsg, ExitReason, IpcReceiverWithContext, IpcSenderWithContext,
+        ClientId, ClientToServerMsg, IpcReceiverWithContext, IpcSenderWithContext,
         ServerToClientMsg,
     },
     shared::default_palette,
@@ -45,18 +50,17 @@ pub(crate) fn set_terminal_size_using_fd(fd: RawFd, columns: u16, rows: u16) {
 }
 
 /// Handle some signals for the child process. This will loop until the child
-/// process exits.
-fn handle_command_exit(mut child: Child) {
+/// process exits, and returns its exit status.
+fn handle_command_exit(mut child: Child) -> Option<ExitStatus> {
     // register the SIGINT signal (TODO handle more signals)
     let mut signals = signal_hook::iterator::Signals::new(&[SIGINT]).unwrap();
-    'handle_exit: loop {
+    loop {
         // test whether the child process has exited
         match child.try_wait() {
-            Ok(Some(_status)) => {
-                // if the child process has exited, break outside of the loop
-                // and exit this function
+            Ok(Some(status)) => {
+                // if the child process has exited, exit this function
                 // TODO: handle errors?
-                break 'handle_exit;
+                return Some(status);
             }
             Ok(None) => {
                 ::std::thread::sleep(::std::time::Duration::from_millis(100));
@@ -67,20 +71,106 @@ fn handle_command_exit(mut child: Child) {
         for signal in signals.pending() {
             if let SIGINT = signal {
                 child.kill().unwrap();
-                child.wait().unwrap();
-                break 'handle_exit;
+                return child.wait().ok();
             }
         }
     }
 }
 
+/// Describes why `program` could not be started in `cwd`, to be shown in its pane.
+fn spawn_error_message(program: &Path, cwd: Option<&Path>, error: &io::Error) -> String {
+    match cwd {
+        Some(cwd) => format!(
+            "Could not start {} in {}: {}",
+            program.display(),
+            cwd.display(),
+            error
+        ),
+        None => format!("Could not start {}: {}", program.display(), error),
+    }
+}
+
+/// Writes why `program` could not be started into the pane (the standard output of the forked
+/// child is the pty), and exits the forked child with a non-zero status.
+fn exit_on_spawn_error(program: &Path, cwd: Option<&Path>, error: io::Error) -> ! {
+    let mut stdout = std::io::stdout();
+    let _ = write!(stdout, "{}\r\n", spawn_error_message(program, cwd, &error));
+    let _ = stdout.flush();
+    ::std::process::exit(1);
+}
+
+/// Describes how a command exited, to be shown in its held pane.
+fn exit_status_message(exit_status: Option<ExitStatus>) -> String {
+    match exit_status {
+        Some(status) => match (status.code(), status.signal()) {
+            (Some(code), _) => format!("Command exited with code {}", code),
+            (None, Some(signal)) => format!("Command was killed by signal {}", signal),
+            (None, None) => "Command exited".to_string(),
+        },
+        None => "Command exited".to_string(),
+    }
+}
+
+/// The code to exit with to pass on how a command exited: its own, or 128 plus the signal that
+/// killed it, as shells do.
+fn exit_code(exit_status: Option<ExitStatus>) -> i32 {
+    match exit_status {
+        Some(status) => status
+            .code()
+            .or_else(|| status.signal().map(|signal| 128 + signal))
+            .unwrap_or(1),
+        None => 1,
+    }
+}
+
+/// Keeps the pane of a command that exited (or could not start) open, showing `how_it_exited`,
+/// until a key is pressed to either run the command again (returning `true`) or close the pane
+/// (returning `false`).
+fn hold_pane_after_exit(how_it_exited: &str) -> bool {
+    let mut stdout = std::io::stdout();
+    let _ = write!(
+        stdout,
+        "\r\n\u{1b}[7m {} \u{1b}[0m  <r> re-run  <q> close\r\n",
+        how_it_exited
+    );
+    let _ = stdout.flush();
+
+    // read single key presses rather than lines
+    let stdin = 0;
+    let orig_termios = termios::tcgetattr(stdin).ok();
+    if let Some(orig_termios) = orig_termios.as_ref() {
+        let mut raw_termios = orig_termios.clone();
+        termios::cfmakeraw(&mut raw_termios);
+        let _ = termios::tcsetattr(stdin, termios::SetArg::TCSANOW, &raw_termios);
+    }
+    let mut buf = [0u8; 1];
+    let rerun = loop {
+        match unistd::read(stdin, &mut buf) {
+            Ok(0) | Err(_) => break false,
+            Ok(_) => match buf[0] {
+                b'r' | b'\r' => break true,
+                // ctrl-c
+                b'q' | 3 => break false,
+                _ => {}
+            },
+        }
+    };
+    if let Some(orig_termios) = orig_termios.as_ref() {
+        let _ = termios::tcsetattr(stdin, termios::SetArg::TCSANOW, orig_termios);
+    }
+    rerun
+}
+
 /// Spawns a new terminal from the parent terminal with [`termios`](termios::Termios)
 /// `orig_termios`.
 ///
-/// If a `file_to_open` is given, the text editor specified by environment variable `EDITOR`
+/// If a file to open is given, the text editor specified by environment variable `EDITOR`
 /// (or `VISUAL`, if `EDITOR` is not set) will be started in the new terminal, with the given
-/// file open. If no file is given, the shell specified by environment variable `SHELL` will
-/// be started in the new terminal.
+/// file open. If a command to run is given, it will be started in the new terminal, in its
+/// working directory and with its environment. If neither is given (or the command has no
+/// program), the shell specified by environment variable `SHELL` will be started in the new
+/// terminal. Whatever runs in it finds `terminal_id` in its `ZELLIJ_PANE_ID` environment
+/// variable.
 ///
 /// # Panics
 ///
@@ -88,7 +178,11 @@ fn handle_command_exit(mut child: Child) {
 /// set.
 // FIXME this should probably be split into different functions, or at least have less levels
 // of indentation in some way
-fn spawn_terminal(file_to_open: Option<PathBuf>, orig_termios: termios::Termios) -> (RawFd, Pid) {
+fn spawn_terminal(
+    terminal_action: Option<TerminalAction>,
+    orig_termios: termios::Termios,
+    terminal_id: u32,
+) -> (RawFd, Pid) {
     let (pid_primary, pid_secondary): (RawFd, Pid) = {
         match forkpty(None, Some(&orig_termios)) {
             Ok(fork_pty_res) => {
@@ -98,29 +192,75 @@ fn spawn_terminal(file_to_open: Option<PathBuf>, orig_termios: termios::Termios)
                         // fcntl(pid_primary, FcntlArg::F_SETFL(OFlag::empty())).expect("could not fcntl");
                         child
                     }
-                    ForkResult::Child => match file_to_open {
-                        Some(file_to_open) => {
-                            if env::var("EDITOR").is_err() && env::var("VISUAL").is_err() {
-                                panic!("Can't edit files if an editor is not defined. To fix: define the EDITOR or VISUAL environment variables with the path to your editor (eg. /usr/bin/vim)");
-                            }
-                            let editor =
-                                env::var("EDITOR").unwrap_or_else(|_| env::var("VISUAL").unwrap());
+                    ForkResult::Child => {
+                        // lets the commands in the pane (and the `zellij` commands run from it)
+                        // know which pane they are in
+                        let pane_id = terminal_id.to_string();
+                        match terminal_action {
+                            Some(TerminalAction::OpenFile(file_to_open)) => {
+                                if env::var("EDITOR").is_err() && env::var("VISUAL").is_err() {
+                                    panic!("Can't edit files if an editor is not defined. To fix: define the EDITOR or VISUAL environment variables with the path to your editor (eg. /usr/bin/vim)");
+                                }
+                                let editor = env::var("EDITOR")
+                                    .unwrap_or_else(|_| env::var("VISUAL").unwrap());
 
-                            let child = Command::new(editor)
-                                .args(&[file_to_open])
-                                .spawn()
-                                .expect("failed to spawn");
-                            handle_command_exit(child);
-                            ::std::process::exit(0);
-                        }
-                        None => {
-                            let child = Command::new(env::var("SHELL").unwrap())
-                                .spawn()
-                                .expect("failed to spawn");
-                            handle_command_exit(child);
-                            ::std::process::exit(0);
+                                let child = Command::new(&editor)
+                                    .args(&[file_to_open])
+                                    .env("ZELLIJ_PANE_ID", &pane_id)
+                                    .spawn()
+                                    .unwrap_or_else(|e| {
+                                        exit_on_spawn_error(Path::new(&editor), None, e)
+                                    });
+                                ::std::process::exit(exit_code(handle_command_exit(child)));
+                            }
+                            Some(TerminalAction::RunCommand(run_command)) => {
+                                let hold_on_exit = run_command.hold_on_exit == Some(true);
+                                let mut command_exit_code = 0;
+                                loop {
+                                    let program =
+                                        run_command.command.clone().unwrap_or_else(|| {
+                                            PathBuf::from(env::var("SHELL").unwrap())
+                                        });
+                                    let mut command = Command::new(&program);
+                                    if run_command.command.is_some() {
+                                        command.args(&run_command.args);
+                                    }
+                                    if let Some(cwd) = run_command.cwd.as_ref() {
+                                        command.current_dir(cwd);
+                                    }
+                                    let cwd = run_command.cwd.as_deref();
+                                    command.env("ZELLIJ_PANE_ID", &pane_id);
+                                    let how_it_exited = match command.envs(&run_command.env).spawn()
+                                    {
+                                        Ok(child) => {
+                                            let exit_status = handle_command_exit(child);
+                                            command_exit_code = exit_code(exit_status);
+                                            exit_status_message(exit_status)
+                                        }
+                                        Err(e) if hold_on_exit => {
+                                            command_exit_code = 1;
+                                            spawn_error_message(&program, cwd, &e)
+                                        }
+                                        Err(e) => exit_on_spawn_error(&program, cwd, e),
+                                    };
+                                    if !hold_on_exit || !hold_pane_after_exit(&how_it_exited) {
+                                        break;
+                                    }
+                                }
+                                ::std::process::exit(command_exit_code);
+                            }
+                            None => {
+                                let shell = env::var("SHELL").unwrap();
+                                let child = Command::new(&shell)
+                                    .env("ZELLIJ_PANE_ID", &pane_id)
+                                    .spawn()
+                                    .unwrap_or_else(|e| {
+                                        exit_on_spawn_error(Path::new(&shell), None, e)
+                                    });
+                                ::std::process::exit(exit_code(handle_command_exit(child)));
+                            }
                         }
-                    },
+                    }
                 };
                 (pid_primary, pid_secondary)
             }
@@ -132,11 +272,43 @@ fn spawn_terminal(file_to_open: Option<PathBuf>, orig_termios: termios::Termios)
     (pid_primary, pid_secondary)
 }
 
+/// The ids of the terminal panes. Unlike the file descriptors of their terminals, which are
+/// reused once a pane is closed, an id is never given to another pane of the session.
+#[derive(Default)]
+struct TerminalIds {
+    next_id: u32,
+    fds: HashMap<u32, RawFd>,
+    ids: HashMap<RawFd, u32>,
+}
+
+impl TerminalIds {
+    fn next_id(&mut self) -> u32 {
+        let id = self.next_id;
+        self.next_id += 1;
+        id
+    }
+    fn insert(&mut self, id: u32, fd: RawFd) {
+        // the pane that had this file descriptor before is closed
+        if let Some(closed_id) = self.ids.insert(fd, id) {
+            self.fds.remove(&closed_id);
+        }
+        self.fds.insert(id, fd);
+    }
+    fn fd(&self, id: u32) -> Option<RawFd> {
+        self.fds.get(&id).copied()
+    }
+    fn id(&self, fd: RawFd) -> Option<u32> {
+        self.ids.get(&fd).copied()
+    }
+}
+
 #[derive(Clone)]
 pub struct ServerOsInputOutput {
     orig_termios: Arc<Mutex<termios::Termios>>,
+    terminal_ids: Arc<Mutex<TerminalIds>>,
     receive_instructions_from_client: Option<Arc<Mutex<IpcReceiverWithContext<ClientToServerMsg>>>>,
-    send_instructions_to_client: Arc<Mutex<Option<IpcSenderWithContext<ServerToClientMsg>>>>,
+    send_instructions_to_clients:
+        Arc<Mutex<HashMap<ClientId, IpcSenderWithContext<ServerToClientMsg>>>>,
 }
 
 // async fn in traits is not supported by rust, so dtolnay's excellent async_trait macro is being
@@ -172,8 +344,8 @@ impl AsyncReader for RawFdAsyncReader {
 pub trait ServerOsApi: Send + Sync {
     /// Sets the size of the terminal associated to file descriptor `fd`.
     fn set_terminal_size_using_fd(&self, fd: RawFd, cols: u16, rows: u16);
-    /// Spawn a new terminal, with an optional file to open in a terminal program.
-    fn spawn_terminal(&self, file_to_open: Option<PathBuf>) -> (RawFd, Pid);
+    /// Spawn a new terminal, with an optional file to open in a terminal program or command to run.
+    fn spawn_terminal(&self, terminal_action: Option<TerminalAction>) -> (RawFd, Pid);
     /// Read bytes from the standard output of the virtual terminal referred to by `fd`.
     fn read_from_tty_stdout(&self, fd: RawFd, buf: &mut [u8]) -> Result<usize, nix::Error>;
     /// Creates an `AsyncReader` that can be used to read from `fd` in an async context
@@ -186,32 +358,44 @@ pub trait ServerOsApi: Send + Sync {
     fn kill(&self, pid: Pid) -> Result<(), nix::Error>;
     /// Returns a [`Box`] pointer to this [`ServerOsApi`] struct.
     fn box_clone(&self) -> Box<dyn ServerOsApi>;
-    /// Receives a message on server-side IPC channel
-    fn recv_from_client(&self) -> (ClientToServerMsg, ErrorContext);
-    /// Sends a message to client
-    fn send_to_client(&self, msg: ServerToClientMsg);
-    /// Adds a sender to client
-    fn add_client_sender(&self);
+    /// Receives a message on server-side IPC channel, unless the client is gone
+    fn recv_from_client(&self) -> Option<(ClientToServerMsg, ErrorContext)>;
+    /// Sends a message to the client `client_id`
+    fn send_to_client(&self, client_id: ClientId, msg: ServerToClientMsg);
+    /// Adds a sender to the client connected to this receiver, as `client_id`
+    fn add_client_sender(&self, client_id: ClientId);
     /// Send to the temporary client
     // A temporary client is the one that hasn't been registered as a client yet.
     // Only the corresponding router thread has access to send messages to it.
     // This can be the case when the client cannot attach to the session,
     // so it tries to connect and then exits, hence temporary.
     fn send_to_temp_client(&self, msg: ServerToClientMsg);
-    /// Removes the sender to client
-    fn remove_client_sender(&self);
+    /// Removes the sender to the client `client_id`
+    fn remove_client_sender(&self, client_id: ClientId);
     /// Update the receiver socket for the client
     fn update_receiver(&mut self, stream: LocalSocketStream);
     fn load_palette(&self) -> Palette;
+    /// Returns the command running in the foreground of the terminal referred to by `fd`,
+    /// along with its arguments and working directory.
+    fn get_foreground_command(&self, fd: RawFd) -> Option<RunCommand>;
+    /// Returns the id of the pane whose terminal is referred to by `fd`.
+    fn terminal_id(&self, fd: RawFd) -> Option<u32>;
+    /// Returns the file descriptor of the terminal of the pane with id `terminal_id`, unless
+    /// there is no such pane anymore.
+    fn terminal_fd(&self, terminal_id: u32) -> Option<RawFd>;
 }
 
 impl ServerOsApi for ServerOsInputOutput {
     fn set_terminal_size_using_fd(&self, fd: RawFd, cols: u16, rows: u16) {
         set_terminal_size_using_fd(fd, cols, rows);
     }
-    fn spawn_terminal(&self, file_to_open: Option<PathBuf>) -> (RawFd, Pid) {
+    fn spawn_terminal(&self, terminal_action: Option<TerminalAction>) -> (RawFd, Pid) {
         let orig_termios = self.orig_termios.lock().unwrap();
-        spawn_terminal(file_to_open, orig_termios.clone())
+        let mut terminal_ids = self.terminal_ids.lock().unwrap();
+        let terminal_id = terminal_ids.next_id();
+        let (fd, pid) = spawn_terminal(terminal_action, orig_termios.clone(), terminal_id);
+        terminal_ids.insert(terminal_id, fd);
+        (fd, pid)
     }
     fn read_from_tty_stdout(&self, fd: RawFd, buf: &mut [u8]) -> Result<usize, nix::Error> {
         unistd::read(fd, buf)
@@ -239,23 +423,25 @@ impl ServerOsApi for ServerOsInputOutput {
         waitpid(pid, None).unwrap();
         Ok(())
     }
-    fn recv_from_client(&self) -> (ClientToServerMsg, ErrorContext) {
+    fn recv_from_client(&self) -> Option<(ClientToServerMsg, ErrorContext)> {
         self.receive_instructions_from_client
             .as_ref()
             .unwrap()
             .lock()
             .unwrap()
             .recv()
+            .ok()
     }
-    fn send_to_client(&self, msg: ServerToClientMsg) {
-        self.send_instructions_to_client
-            .lock()
-            .unwrap()
-            .as_mut()
-            .unwrap()
-            .send(msg);
+    fn send_to_client(&self, client_id: ClientId, msg: ServerToClientMsg) {
+        let mut senders = self.send_instructions_to_clients.lock().unwrap();
+        if let Some(sender) = senders.get_mut(&client_id) {
+            if sender.send(msg).is_err() {
+                // the client is gone: its router removes it from the session once it notices
+                senders.remove(&client_id);
+            }
+        }
     }
-    fn add_client_sender(&self) {
+    fn add_client_sender(&self, client_id: ClientId) {
         let sender = self
             .receive_instructions_from_client
             .as_ref()
@@ -263,17 +449,15 @@ impl ServerOsApi for ServerOsInputOutput {
             .lock()
             .unwrap()
             .get_sender();
-        let old_sender = self
-            .send_instructions_to_client
+        self.send_instructions_to_clients
             .lock()
             .unwrap()
-            .replace(sender);
-        if let Some(mut sender) = old_sender {
-            sender.send(ServerToClientMsg::Exit(ExitReason::ForceDetached));
-        }
+            .insert(client_id, sender);
     }
     fn send_to_temp_client(&self, msg: ServerToClientMsg) {
-        self.receive_instructions_from_client
+        // a temporary client that is gone has nothing left to be told
+        let _ = self
+            .receive_instructions_from_client
             .as_ref()
             .unwrap()
             .lock()
@@ -281,9 +465,11 @@ impl ServerOsApi for ServerOsInputOutput {
             .get_sender()
             .send(msg);
     }
-    fn remove_client_sender(&self) {
-        assert!(self.send_instructions_to_client.lock().unwrap().is_some());
-        *self.send_instructions_to_client.lock().unwrap() = None;
+    fn remove_client_sender(&self, client_id: ClientId) {
+        self.send_instructions_to_clients
+            .lock()
+            .unwrap()
+            .remove(&client_id);
     }
     fn update_receiver(&mut self, stream: LocalSocketStream) {
         self.receive_instructions_from_client =
@@ -292,6 +478,28 @@ impl ServerOsApi for ServerOsInputOutput {
     fn load_palette(&self) -> Palette {
         default_palette()
     }
+    fn get_foreground_command(&self, fd: RawFd) -> Option<RunCommand> {
+        // the foreground process group of a terminal is led by the command running in it
+        let pgid = unistd::tcgetpgrp(fd).ok()?;
+        let process_dir = PathBuf::from("/proc").join(pgid.to_string());
+        let cmdline = fs::read(process_dir.join("cmdline")).ok()?;
+        let mut args = cmdline
+            .split(|byte| *byte == 0)
+            .filter(|arg| !arg.is_empty())
+            .map(|arg| String::from_utf8_lossy(arg).to_string());
+        Some(RunCommand {
+            command: args.next().map(PathBuf::from),
+            args: args.collect(),
+            cwd: fs::read_link(process_dir.join("cwd")).ok(),
+            ..Default::default()
+        })
+    }
+    fn terminal_id(&self, fd: RawFd) -> Option<u32> {
+        self.terminal_ids.lock().unwrap().id(fd)
+    }
+    fn terminal_fd(&self, terminal_id: u32) -> Option<RawFd> {
+        self.terminal_ids.lock().unwrap().fd(terminal_id)
+    }
 }
 
 impl Clone for Box<dyn ServerOsApi> {
@@ -305,7 +513,12 @@ pub fn get_server_os_input() -> Result<ServerOsInputOutput, nix::Error> {
     let orig_termios = Arc::new(Mutex::new(current_termios));
     Ok(ServerOsInputOutput {
         orig_termios,
+        terminal_ids: Arc::new(Mutex::new(TerminalIds::default())),
         receive_instructions_from_client: None,
-        send_instructions_to_client: Arc::new(Mutex::new(None)),
+        send_instructions_to_clients: Arc::new(Mutex::new(HashMap::new())),
     })
 }
+
+#[cfg(test)]
+#[path = "./unit/os_input_output_tests.rs"]
+mod os_input_output_tests;
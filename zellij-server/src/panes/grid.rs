@@ -4,6 +4,7 @@ use std::{
     cmp::Ordering,
     collections::{BTreeSet, VecDeque},
     fmt::{self, Debug, Formatter},
+    path::PathBuf,
     str,
 };
 
@@ -21,6 +22,23 @@ use crate::panes::terminal_character::{
     EMPTY_TERMINAL_CHARACTER,
 };
 
+// parses the `file://hostname/path` url of an OSC 7 working directory report
+fn parse_cwd_url(url: &[u8]) -> Option<PathBuf> {
+    let url = url.strip_prefix(b"file://")?;
+    let path = &url[url.iter().position(|b| *b == b'/')?..];
+    let mut decoded = Vec::with_capacity(path.len());
+    let mut bytes = path.iter();
+    while let Some(byte) = bytes.next() {
+        if *byte == b'%' {
+            let hex = [*bytes.next()?, *bytes.next()?];
+            decoded.push(u8::from_str_radix(str::from_utf8(&hex).ok()?, 16).ok()?);
+        } else {
+            decoded.push(*byte);
+        }
+    }
+    Some(PathBuf::from(String::from_utf8(decoded).ok()?))
+}
+
 // this was copied verbatim from alacritty
 fn parse_number(input: &[u8]) -> Option<u8> {
     if input.is_empty() {
@@ -213,6 +231,8 @@ pub struct Grid {
     pub width: usize,
     pub height: usize,
     pub pending_messages_to_pty: Vec<Vec<u8>>,
+    pub cwd: Option<PathBuf>,  // as last reported by the shell with OSC 7
+    pub title: Option<String>, // as last set with OSC 0 or 2
 }
 
 impl Debug for Grid {
@@ -250,6 +270,8 @@ impl Grid {
             clear_viewport_before_rendering: false,
             active_charset: Default::default(),
             pending_messages_to_pty: vec![],
+            cwd: None,
+            title: None,
             colors,
         }
     }
@@ -537,6 +559,45 @@ impl Grid {
         }
         lines
     }
+    /// Returns the lines on screen (and, if `with_scrollback`, those above them) as text,
+    /// along with their styles as ANSI escape sequences if `with_ansi`.
+    pub fn capture(&self, with_scrollback: bool, with_ansi: bool) -> String {
+        // when scrolled up, the lines below the viewport are still on the screen of the pane
+        let rows: Vec<&Row> = self
+            .lines_above
+            .iter()
+            .chain(self.viewport.iter())
+            .chain(self.lines_below.iter())
+            .collect();
+        let first_row = if with_scrollback {
+            0
+        } else {
+            rows.len().saturating_sub(self.viewport.len())
+        };
+        let mut captured = String::new();
+        for row in &rows[first_row..] {
+            let mut line = String::new();
+            let mut character_styles = CharacterStyles::new();
+            for t_character in &row.columns {
+                if with_ansi {
+                    if let Some(new_styles) =
+                        character_styles.update_and_return_diff(&t_character.styles)
+                    {
+                        line.push_str(&new_styles.to_string());
+                    }
+                }
+                line.push(t_character.character);
+            }
+            if with_ansi {
+                line.push_str("\u{1b}[m");
+            } else {
+                line.truncate(line.trim_end().len());
+            }
+            captured.push_str(&line);
+            captured.push('\n');
+        }
+        captured
+    }
     pub fn cursor_coordinates(&self) -> Option<(usize, usize)> {
         if self.cursor.is_hidden {
             None
@@ -1051,14 +1112,24 @@ impl Perform for Grid {
             // Set window title.
             b"0" | b"2" => {
                 if params.len() >= 2 {
-                    let _title = params[1..]
+                    let title = params[1..]
                         .iter()
                         .flat_map(|x| str::from_utf8(x))
                         .collect::<Vec<&str>>()
                         .join(";")
                         .trim()
                         .to_owned();
-                    // TBD: do something with title?
+                    self.title = Some(title);
+                }
+            }
+
+            // Report the working directory.
+            b"7" => {
+                if params.len() >= 2 {
+                    // the path may itself contain `;`
+                    if let Some(cwd) = parse_cwd_url(&params[1..].join(&b';')) {
+                        self.cwd = Some(cwd);
+                    }
                 }
             }
 
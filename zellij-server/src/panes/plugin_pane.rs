@@ -1,3 +1,4 @@
+use std::path::PathBuf;
 use std::sync::mpsc::channel;
 use std::time::Instant;
 use std::unimplemented;
@@ -19,6 +20,7 @@ pub(crate) struct PluginPane {
     pub max_height: Option<usize>,
     pub max_width: Option<usize>,
     pub active_at: Instant,
+    pub plugin_path: PathBuf,
 }
 
 impl PluginPane {
@@ -26,6 +28,7 @@ impl PluginPane {
         pid: u32,
         position_and_size: PositionAndSize,
         send_plugin_instructions: SenderWithContext<PluginInstruction>,
+        plugin_path: PathBuf,
     ) -> Self {
         Self {
             pid,
@@ -38,6 +41,7 @@ impl PluginPane {
             max_height: None,
             max_width: None,
             active_at: Instant::now(),
+            plugin_path,
         }
     }
 }
@@ -74,6 +78,13 @@ impl Pane for PluginPane {
         self.position_and_size = *position_and_size;
         self.should_render = true;
     }
+    fn relocate(&mut self, position_and_size: &PositionAndSize) {
+        self.position_and_size.x = position_and_size.x;
+        self.position_and_size.y = position_and_size.y;
+        self.position_and_size.columns = position_and_size.columns;
+        self.position_and_size.rows = position_and_size.rows;
+        self.should_render = true;
+    }
     // FIXME: This is obviously a bit outdated and needs the x and y moved into `size`
     fn override_size_and_position(&mut self, x: usize, y: usize, size: &PositionAndSize) {
         let position_and_size_override = PositionAndSize {
@@ -99,6 +110,9 @@ impl Pane for PluginPane {
     fn position_and_size_override(&self) -> Option<PositionAndSize> {
         self.position_and_size_override
     }
+    fn base_position_and_size(&self) -> PositionAndSize {
+        self.position_and_size
+    }
     fn should_render(&self) -> bool {
         self.should_render
     }
@@ -210,6 +224,9 @@ impl Pane for PluginPane {
     fn max_width(&self) -> Option<usize> {
         self.max_width
     }
+    fn plugin_path(&self) -> Option<PathBuf> {
+        Some(self.plugin_path.clone())
+    }
     fn invisible_borders(&self) -> bool {
         self.invisible_borders
     }
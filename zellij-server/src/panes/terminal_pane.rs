@@ -2,6 +2,7 @@ use zellij_utils::{vte, zellij_tile};
 
 use std::fmt::Debug;
 use std::os::unix::io::RawFd;
+use std::path::PathBuf;
 use std::time::Instant;
 use zellij_tile::data::Palette;
 use zellij_utils::pane_size::PositionAndSize;
@@ -31,6 +32,8 @@ pub struct TerminalPane {
     pub max_width: Option<usize>,
     pub active_at: Instant,
     pub colors: Palette,
+    pub sync_group: Option<String>,
+    pub name: Option<String>,
     vte_parser: vte::Parser,
 }
 
@@ -56,6 +59,11 @@ impl Pane for TerminalPane {
         self.position_and_size.rows = position_and_size.rows;
         self.reflow_lines();
     }
+    fn relocate(&mut self, position_and_size: &PositionAndSize) {
+        self.position_and_size.x = position_and_size.x;
+        self.position_and_size.y = position_and_size.y;
+        self.change_pos_and_size(position_and_size);
+    }
     fn override_size_and_position(&mut self, x: usize, y: usize, size: &PositionAndSize) {
         let position_and_size_override = PositionAndSize {
             x,
@@ -123,6 +131,9 @@ impl Pane for TerminalPane {
     fn position_and_size_override(&self) -> Option<PositionAndSize> {
         self.position_and_size_override
     }
+    fn base_position_and_size(&self) -> PositionAndSize {
+        self.position_and_size
+    }
     fn should_render(&self) -> bool {
         self.grid.should_render
     }
@@ -150,6 +161,25 @@ impl Pane for TerminalPane {
     fn max_width(&self) -> Option<usize> {
         self.max_width
     }
+    fn cwd(&self) -> Option<PathBuf> {
+        self.grid.cwd.clone()
+    }
+    fn sync_group(&self) -> Option<String> {
+        self.sync_group.clone()
+    }
+    fn set_sync_group(&mut self, sync_group: Option<String>) {
+        self.sync_group = sync_group;
+    }
+    fn set_name(&mut self, name: String) {
+        self.name = Some(name);
+    }
+    fn title(&self) -> Option<String> {
+        // a name given to the pane is kept over the titles its programs set
+        self.name.clone().or_else(|| self.grid.title.clone())
+    }
+    fn capture(&self, with_scrollback: bool, with_ansi: bool) -> Option<String> {
+        Some(self.grid.capture(with_scrollback, with_ansi))
+    }
     fn render(&mut self) -> Option<String> {
         if self.should_render() {
             let mut vte_output = String::new();
@@ -299,6 +329,8 @@ impl TerminalPane {
             vte_parser: vte::Parser::new(),
             active_at: Instant::now(),
             colors: palette,
+            sync_group: None,
+            name: None,
         }
     }
     pub fn get_x(&self) -> usize {
@@ -555,3 +555,40 @@ fn wrap_wide_characters_at_the_end_of_the_line() {
     }
     assert_snapshot!(format!("{:?}", grid));
 }
+
+#[test]
+fn osc_7_reports_the_working_directory() {
+    let mut vte_parser = vte::Parser::new();
+    let mut grid = Grid::new(41, 110, Palette::default());
+    let content = b"\x1b]7;file://myhost/home/user/my%20project\x07";
+    for byte in content.iter() {
+        vte_parser.advance(&mut grid, *byte);
+    }
+    assert_eq!(
+        grid.cwd,
+        Some(std::path::PathBuf::from("/home/user/my project"))
+    );
+}
+
+#[test]
+fn osc_2_sets_the_title() {
+    let mut vte_parser = vte::Parser::new();
+    let mut grid = Grid::new(41, 110, Palette::default());
+    let content = b"\x1b]2;vim main.rs\x07";
+    for byte in content.iter() {
+        vte_parser.advance(&mut grid, *byte);
+    }
+    assert_eq!(grid.title, Some("vim main.rs".to_string()));
+}
+
+#[test]
+fn capture_with_and_without_scrollback() {
+    let mut vte_parser = vte::Parser::new();
+    let mut grid = Grid::new(3, 10, Palette::default());
+    let content = b"one\r\ntwo\r\nthree\r\nfour";
+    for byte in content.iter() {
+        vte_parser.advance(&mut grid, *byte);
+    }
+    assert_eq!(grid.capture(false, false), "two\nthree\nfour\n");
+    assert_eq!(grid.capture(true, false), "one\ntwo\nthree\nfour\n");
+}
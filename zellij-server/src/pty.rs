@@ -12,12 +12,16 @@ use crate::{
     panes::PaneId,
     screen::ScreenInstruction,
     thread_bus::{Bus, ThreadSenders},
-    ui::layout::Layout,
     wasm_vm::PluginInstruction,
     ServerInstruction,
 };
 use zellij_utils::{
     errors::{get_current_ctx, ContextType, PtyContext},
+    input::{
+        actions::{Direction, LayoutPreset},
+        command::{RunCommand, TerminalAction},
+        layout::{Layout, SessionLayout},
+    },
     logging::debug_to_file,
 };
 
@@ -26,10 +30,13 @@ pub type VteBytes = Vec<u8>;
 /// Instructions related to PTYs (pseudoterminals).
 #[derive(Clone, Debug)]
 pub(crate) enum PtyInstruction {
-    SpawnTerminal(Option<PathBuf>),
-    SpawnTerminalVertically(Option<PathBuf>),
-    SpawnTerminalHorizontally(Option<PathBuf>),
-    NewTab,
+    SpawnTerminal(Option<TerminalAction>),
+    SpawnTerminalVertically(Option<TerminalAction>),
+    SpawnTerminalHorizontally(Option<TerminalAction>),
+    RunCommand(RunCommand, Option<Direction>, Option<String>),
+    NewTab(Option<PathBuf>),
+    BreakPane(Option<PathBuf>),
+    OpenInitialTabs,
     ClosePane(PaneId),
     CloseTab(Vec<PaneId>),
     Exit,
@@ -41,9 +48,12 @@ impl From<&PtyInstruction> for PtyContext {
             PtyInstruction::SpawnTerminal(_) => PtyContext::SpawnTerminal,
             PtyInstruction::SpawnTerminalVertically(_) => PtyContext::SpawnTerminalVertically,
             PtyInstruction::SpawnTerminalHorizontally(_) => PtyContext::SpawnTerminalHorizontally,
+            PtyInstruction::RunCommand(..) => PtyContext::RunCommand,
             PtyInstruction::ClosePane(_) => PtyContext::ClosePane,
             PtyInstruction::CloseTab(_) => PtyContext::CloseTab,
-            PtyInstruction::NewTab => PtyContext::NewTab,
+            PtyInstruction::NewTab(_) => PtyContext::NewTab,
+            PtyInstruction::BreakPane(_) => PtyContext::BreakPane,
+            PtyInstruction::OpenInitialTabs => PtyContext::OpenInitialTabs,
             PtyInstruction::Exit => PtyContext::Exit,
         }
     }
@@ -53,46 +63,86 @@ pub(crate) struct Pty {
     pub bus: Bus<PtyInstruction>,
     pub id_to_child_pid: HashMap<RawFd, Pid>,
     debug_to_file: bool,
+    hold_on_exit: bool,
     task_handles: HashMap<RawFd, JoinHandle<()>>,
 }
 
-pub(crate) fn pty_thread_main(mut pty: Pty, maybe_layout: Option<Layout>) {
+pub(crate) fn pty_thread_main(mut pty: Pty, maybe_layout: Option<SessionLayout>) {
     loop {
         let (event, mut err_ctx) = pty.bus.recv().expect("failed to receive event on channel");
         err_ctx.add_call(ContextType::Pty((&event).into()));
         match event {
-            PtyInstruction::SpawnTerminal(file_to_open) => {
-                let pid = pty.spawn_terminal(file_to_open);
+            PtyInstruction::SpawnTerminal(terminal_action) => {
+                let pid = pty.spawn_terminal(terminal_action);
                 pty.bus
                     .senders
                     .send_to_screen(ScreenInstruction::NewPane(PaneId::Terminal(pid)))
                     .unwrap();
             }
-            PtyInstruction::SpawnTerminalVertically(file_to_open) => {
-                let pid = pty.spawn_terminal(file_to_open);
+            PtyInstruction::SpawnTerminalVertically(terminal_action) => {
+                let pid = pty.spawn_terminal(terminal_action);
                 pty.bus
                     .senders
                     .send_to_screen(ScreenInstruction::VerticalSplit(PaneId::Terminal(pid)))
                     .unwrap();
             }
-            PtyInstruction::SpawnTerminalHorizontally(file_to_open) => {
-                let pid = pty.spawn_terminal(file_to_open);
+            PtyInstruction::SpawnTerminalHorizontally(terminal_action) => {
+                let pid = pty.spawn_terminal(terminal_action);
                 pty.bus
                     .senders
                     .send_to_screen(ScreenInstruction::HorizontalSplit(PaneId::Terminal(pid)))
                     .unwrap();
             }
-            PtyInstruction::NewTab => {
-                if let Some(layout) = maybe_layout.clone() {
-                    pty.spawn_terminals_for_layout(layout);
-                } else {
-                    let pid = pty.spawn_terminal(None);
+            PtyInstruction::RunCommand(run_command, direction, name) => {
+                let pid = pty.spawn_terminal(Some(TerminalAction::RunCommand(run_command)));
+                let pane_id = PaneId::Terminal(pid);
+                let screen_instruction = match direction {
+                    Some(Direction::Left) | Some(Direction::Right) => {
+                        ScreenInstruction::VerticalSplit(pane_id)
+                    }
+                    Some(Direction::Up) | Some(Direction::Down) => {
+                        ScreenInstruction::HorizontalSplit(pane_id)
+                    }
+                    // No direction specified - try to put it in the biggest available spot
+                    None => ScreenInstruction::NewPane(pane_id),
+                };
+                pty.bus.senders.send_to_screen(screen_instruction).unwrap();
+                if let Some(name) = name {
                     pty.bus
                         .senders
-                        .send_to_screen(ScreenInstruction::NewTab(pid))
+                        .send_to_screen(ScreenInstruction::SetPaneName(pane_id, name))
                         .unwrap();
                 }
             }
+            PtyInstruction::NewTab(cwd) => {
+                if let Some(template) = maybe_layout.as_ref().and_then(|l| l.template.clone()) {
+                    pty.spawn_terminals_for_layout(template, String::new(), cwd);
+                } else {
+                    pty.spawn_terminal_in_new_tab(cwd);
+                }
+            }
+            PtyInstruction::BreakPane(cwd) => {
+                let layout = maybe_layout
+                    .as_ref()
+                    .and_then(|l| l.template.clone())
+                    .unwrap_or_else(|| Layout::from_preset(LayoutPreset::EvenHorizontal, 1));
+                pty.spawn_terminals_for_broken_pane(layout, cwd);
+            }
+            PtyInstruction::OpenInitialTabs => match maybe_layout.as_ref() {
+                Some(session_layout) if !session_layout.tabs.is_empty() => {
+                    for tab in session_layout.tabs.iter() {
+                        pty.spawn_terminals_for_layout(tab.layout(), tab.name.clone(), None);
+                    }
+                    if let Some(tab_position) = session_layout.focused_tab_position() {
+                        // positions are counted from 1 in `GoToTab`
+                        pty.bus
+                            .senders
+                            .send_to_screen(ScreenInstruction::GoToTab(tab_position as u32 + 1))
+                            .unwrap();
+                    }
+                }
+                _ => pty.spawn_terminal_in_new_tab(None),
+            },
             PtyInstruction::ClosePane(id) => {
                 pty.close_pane(id);
                 pty.bus
@@ -205,22 +255,56 @@ fn stream_terminal_bytes(
     })
 }
 
+/// Runs `terminal_action` in `cwd`, unless it already has a working directory.
+fn with_default_cwd(
+    terminal_action: Option<TerminalAction>,
+    cwd: Option<PathBuf>,
+) -> Option<TerminalAction> {
+    match (terminal_action, cwd) {
+        (None, Some(cwd)) => Some(TerminalAction::RunCommand(RunCommand {
+            cwd: Some(cwd),
+            ..Default::default()
+        })),
+        (Some(TerminalAction::RunCommand(mut run_command)), Some(cwd))
+            if run_command.cwd.is_none() =>
+        {
+            run_command.cwd = Some(cwd);
+            Some(TerminalAction::RunCommand(run_command))
+        }
+        (terminal_action, _) => terminal_action,
+    }
+}
+
 impl Pty {
-    pub fn new(bus: Bus<PtyInstruction>, debug_to_file: bool) -> Self {
+    pub fn new(bus: Bus<PtyInstruction>, debug_to_file: bool, hold_on_exit: bool) -> Self {
         Pty {
             bus,
             id_to_child_pid: HashMap::new(),
             debug_to_file,
+            hold_on_exit,
             task_handles: HashMap::new(),
         }
     }
-    pub fn spawn_terminal(&mut self, file_to_open: Option<PathBuf>) -> RawFd {
+    /// Applies the `hold_on_exit` option to commands that don't set it themselves.
+    fn with_hold_on_exit(&self, terminal_action: Option<TerminalAction>) -> Option<TerminalAction> {
+        match terminal_action {
+            Some(TerminalAction::RunCommand(mut run_command))
+                if run_command.command.is_some() && run_command.hold_on_exit.is_none() =>
+            {
+                run_command.hold_on_exit = Some(self.hold_on_exit);
+                Some(TerminalAction::RunCommand(run_command))
+            }
+            terminal_action => terminal_action,
+        }
+    }
+    pub fn spawn_terminal(&mut self, terminal_action: Option<TerminalAction>) -> RawFd {
+        let terminal_action = self.with_hold_on_exit(terminal_action);
         let (pid_primary, pid_secondary): (RawFd, Pid) = self
             .bus
             .os_input
             .as_mut()
             .unwrap()
-            .spawn_terminal(file_to_open);
+            .spawn_terminal(terminal_action);
         let task_handle = stream_terminal_bytes(
             pid_primary,
             self.bus.senders.clone(),
@@ -231,22 +315,67 @@ impl Pty {
         self.id_to_child_pid.insert(pid_primary, pid_secondary);
         pid_primary
     }
-    pub fn spawn_terminals_for_layout(&mut self, layout: Layout) {
-        let total_panes = layout.total_terminal_panes();
-        let mut new_pane_pids = vec![];
-        for _ in 0..total_panes {
-            let (pid_primary, pid_secondary): (RawFd, Pid) =
-                self.bus.os_input.as_mut().unwrap().spawn_terminal(None);
-            self.id_to_child_pid.insert(pid_primary, pid_secondary);
-            new_pane_pids.push(pid_primary);
-        }
+    pub fn spawn_terminal_in_new_tab(&mut self, cwd: Option<PathBuf>) {
+        let pid = self.spawn_terminal(with_default_cwd(None, cwd));
+        self.bus
+            .senders
+            .send_to_screen(ScreenInstruction::NewTab(pid))
+            .unwrap();
+    }
+    /// Spawns the terminals of `layout` in a new tab, those without a working directory of
+    /// their own starting in `cwd`.
+    pub fn spawn_terminals_for_layout(
+        &mut self,
+        layout: Layout,
+        tab_name: String,
+        cwd: Option<PathBuf>,
+    ) {
+        let new_pane_pids = self.spawn_terminals(layout.terminal_pane_actions(), cwd);
         self.bus
             .senders
             .send_to_screen(ScreenInstruction::ApplyLayout(
                 layout,
                 new_pane_pids.clone(),
+                tab_name,
             ))
             .unwrap();
+        self.stream_terminals(new_pane_pids);
+    }
+    /// Spawns the terminals of `layout` for the tab of a pane broken out of its own tab,
+    /// leaving the first terminal slot of the layout to that pane.
+    pub fn spawn_terminals_for_broken_pane(&mut self, layout: Layout, cwd: Option<PathBuf>) {
+        let terminal_actions = layout.terminal_pane_actions().into_iter().skip(1);
+        let new_pane_pids = self.spawn_terminals(terminal_actions, cwd);
+        self.bus
+            .senders
+            .send_to_screen(ScreenInstruction::NewTabForBrokenPane(
+                layout,
+                new_pane_pids.clone(),
+            ))
+            .unwrap();
+        self.stream_terminals(new_pane_pids);
+    }
+    fn spawn_terminals(
+        &mut self,
+        terminal_actions: impl IntoIterator<Item = Option<TerminalAction>>,
+        cwd: Option<PathBuf>,
+    ) -> Vec<RawFd> {
+        let mut new_pane_pids = vec![];
+        for terminal_action in terminal_actions {
+            let terminal_action = with_default_cwd(terminal_action, cwd.clone());
+            let terminal_action = self.with_hold_on_exit(terminal_action);
+            let (pid_primary, pid_secondary): (RawFd, Pid) = self
+                .bus
+                .os_input
+                .as_mut()
+                .unwrap()
+                .spawn_terminal(terminal_action);
+            self.id_to_child_pid.insert(pid_primary, pid_secondary);
+            new_pane_pids.push(pid_primary);
+        }
+        new_pane_pids
+    }
+    fn stream_terminals(&mut self, new_pane_pids: Vec<RawFd>) {
         for id in new_pane_pids {
             let task_handle = stream_terminal_bytes(
                 id,
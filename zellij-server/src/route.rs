@@ -3,7 +3,7 @@ use std::sync::{Arc, RwLock};
 use zellij_utils::zellij_tile::data::Event;
 
 use crate::{
-    os_input_output::ServerOsApi, pty::PtyInstruction, screen::ScreenInstruction,
+    os_input_output::ServerOsApi, panes::PaneId, pty::PtyInstruction, screen::ScreenInstruction,
     wasm_vm::PluginInstruction, ServerInstruction, SessionMetaData, SessionState,
 };
 use zellij_utils::{
@@ -12,7 +12,7 @@ use zellij_utils::{
         actions::{Action, Direction},
         get_mode_info,
     },
-    ipc::{ClientToServerMsg, ExitReason, ServerToClientMsg},
+    ipc::{ClientId, ClientToServerMsg, ClientType, ExitReason, ServerToClientMsg},
 };
 
 fn route_action(
@@ -20,6 +20,7 @@ fn route_action(
     session: &SessionMetaData,
     os_input: &dyn ServerOsApi,
     to_server: &SenderWithContext<ServerInstruction>,
+    client_id: ClientId,
 ) -> bool {
     let mut should_break = false;
     match action {
@@ -132,16 +133,89 @@ fn route_action(
                 .send_to_screen(ScreenInstruction::ToggleActiveTerminalFullscreen)
                 .unwrap();
         }
+        Action::SwapPane(direction) => {
+            session
+                .senders
+                .send_to_screen(ScreenInstruction::SwapPane(direction))
+                .unwrap();
+        }
+        Action::MovePane(direction) => {
+            session
+                .senders
+                .send_to_screen(ScreenInstruction::MovePane(direction))
+                .unwrap();
+        }
+        Action::RotatePanes => {
+            session
+                .senders
+                .send_to_screen(ScreenInstruction::RotatePanes(false))
+                .unwrap();
+        }
+        Action::RotatePanesBackwards => {
+            session
+                .senders
+                .send_to_screen(ScreenInstruction::RotatePanes(true))
+                .unwrap();
+        }
+        Action::BreakPane => {
+            session
+                .senders
+                .send_to_screen(ScreenInstruction::BreakPane)
+                .unwrap();
+        }
+        Action::JoinPaneToTab(i) => {
+            session
+                .senders
+                .send_to_screen(ScreenInstruction::JoinPaneToTab(i))
+                .unwrap();
+        }
+        Action::NextLayout => {
+            session
+                .senders
+                .send_to_screen(ScreenInstruction::NextLayout)
+                .unwrap();
+        }
+        Action::SelectLayout(preset) => {
+            session
+                .senders
+                .send_to_screen(ScreenInstruction::SelectLayout(preset))
+                .unwrap();
+        }
+        Action::EqualizePanes => {
+            session
+                .senders
+                .send_to_screen(ScreenInstruction::EqualizePanes)
+                .unwrap();
+        }
+        Action::SetPaneWidth(width) => {
+            session
+                .senders
+                .send_to_screen(ScreenInstruction::SetPaneWidth(width))
+                .unwrap();
+        }
+        Action::SetPaneHeight(height) => {
+            session
+                .senders
+                .send_to_screen(ScreenInstruction::SetPaneHeight(height))
+                .unwrap();
+        }
+        Action::DumpLayout(path) => {
+            session
+                .senders
+                .send_to_screen(ScreenInstruction::DumpLayout(path, None))
+                .unwrap();
+        }
         Action::NewPane(direction) => {
-            let pty_instr = match direction {
-                Some(Direction::Left) => PtyInstruction::SpawnTerminalVertically(None),
-                Some(Direction::Right) => PtyInstruction::SpawnTerminalVertically(None),
-                Some(Direction::Up) => PtyInstruction::SpawnTerminalHorizontally(None),
-                Some(Direction::Down) => PtyInstruction::SpawnTerminalHorizontally(None),
-                // No direction specified - try to put it in the biggest available spot
-                None => PtyInstruction::SpawnTerminal(None),
-            };
-            session.senders.send_to_pty(pty_instr).unwrap();
+            session
+                .senders
+                .send_to_screen(ScreenInstruction::NewPaneFromFocus(direction))
+                .unwrap();
+        }
+        Action::Run(run_command, direction, name) => {
+            session
+                .senders
+                .send_to_pty(PtyInstruction::RunCommand(run_command, direction, name))
+                .unwrap();
         }
         Action::CloseFocus => {
             session
@@ -150,7 +224,10 @@ fn route_action(
                 .unwrap();
         }
         Action::NewTab => {
-            session.senders.send_to_pty(PtyInstruction::NewTab).unwrap();
+            session
+                .senders
+                .send_to_screen(ScreenInstruction::NewTabFromFocus)
+                .unwrap();
         }
         Action::GoToNextTab => {
             session
@@ -170,6 +247,18 @@ fn route_action(
                 .send_to_screen(ScreenInstruction::ToggleActiveSyncTab)
                 .unwrap();
         }
+        Action::JoinSyncGroup(sync_group) => {
+            session
+                .senders
+                .send_to_screen(ScreenInstruction::JoinSyncGroup(sync_group))
+                .unwrap();
+        }
+        Action::LeaveSyncGroup => {
+            session
+                .senders
+                .send_to_screen(ScreenInstruction::LeaveSyncGroup)
+                .unwrap();
+        }
         Action::CloseTab => {
             session
                 .senders
@@ -182,18 +271,40 @@ fn route_action(
                 .send_to_screen(ScreenInstruction::GoToTab(i))
                 .unwrap();
         }
+        Action::MoveTab(direction) => {
+            let screen_instr = match direction {
+                Direction::Left | Direction::Up => ScreenInstruction::MoveTabLeft,
+                Direction::Right | Direction::Down => ScreenInstruction::MoveTabRight,
+            };
+            session.senders.send_to_screen(screen_instr).unwrap();
+        }
+        Action::MoveTabToPosition(i) => {
+            session
+                .senders
+                .send_to_screen(ScreenInstruction::MoveTabToPosition(i))
+                .unwrap();
+        }
         Action::TabNameInput(c) => {
             session
                 .senders
                 .send_to_screen(ScreenInstruction::UpdateTabName(c))
                 .unwrap();
         }
+        Action::RenameSession(name) => {
+            to_server
+                .send(ServerInstruction::RenameSession(name, None))
+                .unwrap();
+        }
+        // the router keeps the name being typed, see `update_session_name_input`
+        Action::SessionNameInput(_) => {}
         Action::Quit => {
             to_server.send(ServerInstruction::ClientExit).unwrap();
             should_break = true;
         }
         Action::Detach => {
-            to_server.send(ServerInstruction::DetachSession).unwrap();
+            to_server
+                .send(ServerInstruction::DetachSession(client_id))
+                .unwrap();
             should_break = true;
         }
         Action::NoOp => {}
@@ -201,52 +312,195 @@ fn route_action(
     should_break
 }
 
+/// Applies the input of the session rename mode to the name typed so far, and returns the
+/// name once it is entered.
+fn update_session_name_input(name: &mut String, input: &[u8]) -> Option<String> {
+    match input {
+        // starts a new name, or gives up on this one
+        [0] | [27] => name.clear(),
+        // backspace
+        [8] | [127] => {
+            name.pop();
+        }
+        [b'\n'] | [b'\r'] => return Some(std::mem::take(name)),
+        _ => name.push_str(&String::from_utf8_lossy(input)),
+    }
+    None
+}
+
 pub(crate) fn route_thread_main(
     session_data: Arc<RwLock<Option<SessionMetaData>>>,
     session_state: Arc<RwLock<SessionState>>,
     os_input: Box<dyn ServerOsApi>,
     to_server: SenderWithContext<ServerInstruction>,
+    client_id: ClientId,
 ) {
+    let mut client_type = ClientType::Writer;
+    let mut session_name_input = String::new();
     loop {
-        let (instruction, err_ctx) = os_input.recv_from_client();
+        let (instruction, err_ctx) = match os_input.recv_from_client() {
+            Some(instruction) => instruction,
+            None => {
+                // the client went away without telling
+                os_input.remove_client_sender(client_id);
+                let _ = to_server.send(ServerInstruction::RemoveClient(client_id));
+                break;
+            }
+        };
         err_ctx.update_thread_ctx();
         let rlocked_sessions = session_data.read().unwrap();
 
         match instruction {
+            ClientToServerMsg::Action(action)
+                if client_type == ClientType::Reader && action != Action::Detach =>
+            {
+                // read-only clients can only watch, but their input thread might be
+                // waiting for this action to be done
+                os_input.send_to_client(client_id, ServerToClientMsg::UnblockInputThread);
+            }
+            ClientToServerMsg::Action(Action::SessionNameInput(input)) => {
+                if let Some(new_name) = update_session_name_input(&mut session_name_input, &input) {
+                    // an empty name means the rename was given up
+                    if !new_name.is_empty() {
+                        to_server
+                            .send(ServerInstruction::RenameSession(new_name, None))
+                            .unwrap();
+                    }
+                }
+            }
             ClientToServerMsg::Action(action) => {
                 if let Some(rlocked_sessions) = rlocked_sessions.as_ref() {
-                    if route_action(action, rlocked_sessions, &*os_input, &to_server) {
+                    if route_action(action, rlocked_sessions, &*os_input, &to_server, client_id) {
                         break;
                     }
                 }
             }
             ClientToServerMsg::TerminalResize(new_size) => {
-                rlocked_sessions
-                    .as_ref()
-                    .unwrap()
-                    .senders
-                    .send_to_screen(ScreenInstruction::TerminalResize(new_size))
+                to_server
+                    .send(ServerInstruction::TerminalResize(client_id, new_size))
                     .unwrap();
             }
-            ClientToServerMsg::NewClient(..) => {
+            ClientToServerMsg::NewClient(attrs, opts, options) => {
                 if *session_state.read().unwrap() != SessionState::Uninitialized {
                     os_input.send_to_temp_client(ServerToClientMsg::Exit(ExitReason::Error(
                         "Cannot add new client".into(),
                     )));
                 } else {
-                    os_input.add_client_sender();
-                    to_server.send(instruction.into()).unwrap();
+                    os_input.add_client_sender(client_id);
+                    to_server
+                        .send(ServerInstruction::NewClient(
+                            attrs, opts, options, client_id,
+                        ))
+                        .unwrap();
                 }
             }
-            ClientToServerMsg::AttachClient(_, force) => {
-                if *session_state.read().unwrap() == SessionState::Attached && !force {
-                    os_input.send_to_temp_client(ServerToClientMsg::Exit(ExitReason::CannotAttach));
-                } else {
-                    os_input.add_client_sender();
-                    to_server.send(instruction.into()).unwrap();
+            ClientToServerMsg::AttachClient(attrs, force, attach_as) => {
+                client_type = attach_as;
+                // read-only clients cannot detach the others
+                let force = force && attach_as == ClientType::Writer;
+                os_input.add_client_sender(client_id);
+                to_server
+                    .send(ServerInstruction::AttachClient(
+                        attrs, force, attach_as, client_id,
+                    ))
+                    .unwrap();
+            }
+            ClientToServerMsg::QuerySessionInfo => {
+                os_input.add_client_sender(client_id);
+                to_server
+                    .send(ServerInstruction::QuerySessionInfo(client_id))
+                    .unwrap();
+            }
+            ClientToServerMsg::QuerySessionTree => {
+                os_input.add_client_sender(client_id);
+                to_server
+                    .send(ServerInstruction::QuerySessionTree(client_id))
+                    .unwrap();
+            }
+            ClientToServerMsg::CapturePane(pane_id, with_scrollback, with_ansi) => {
+                os_input.add_client_sender(client_id);
+                // the focused pane, or the pane with the given id if there is one
+                let pane_id = match pane_id {
+                    Some(pane_id) => os_input
+                        .terminal_fd(pane_id)
+                        .map(|fd| Some(PaneId::Terminal(fd))),
+                    None => Some(None),
+                };
+                match (rlocked_sessions.as_ref(), pane_id) {
+                    (Some(session), Some(pane_id)) => session
+                        .senders
+                        .send_to_screen(ScreenInstruction::CapturePane(
+                            pane_id,
+                            with_scrollback,
+                            with_ansi,
+                            client_id,
+                        ))
+                        .unwrap(),
+                    _ => os_input.send_to_client(client_id, ServerToClientMsg::PaneContents(None)),
                 }
             }
-            ClientToServerMsg::ClientExited => break,
+            ClientToServerMsg::WriteToPane(..)
+            | ClientToServerMsg::KillSession
+            | ClientToServerMsg::RenameSession(_)
+            | ClientToServerMsg::DumpLayout(_)
+                if client_type == ClientType::Reader =>
+            {
+                // read-only clients cannot change the session
+            }
+            ClientToServerMsg::RenameSession(new_name) => {
+                os_input.add_client_sender(client_id);
+                to_server
+                    .send(ServerInstruction::RenameSession(new_name, Some(client_id)))
+                    .unwrap();
+            }
+            ClientToServerMsg::DumpLayout(path) => {
+                os_input.add_client_sender(client_id);
+                match rlocked_sessions.as_ref() {
+                    Some(session) => session
+                        .senders
+                        .send_to_screen(ScreenInstruction::DumpLayout(path, Some(client_id)))
+                        .unwrap(),
+                    None => os_input.send_to_client(
+                        client_id,
+                        ServerToClientMsg::ActionResult(Err("the session is closing".to_string())),
+                    ),
+                }
+            }
+            ClientToServerMsg::WriteToPane(pane_id, bytes) => {
+                os_input.add_client_sender(client_id);
+                match (rlocked_sessions.as_ref(), os_input.terminal_fd(pane_id)) {
+                    (Some(session), Some(terminal_fd)) => session
+                        .senders
+                        .send_to_screen(ScreenInstruction::WriteToTerminal(
+                            terminal_fd,
+                            bytes,
+                            client_id,
+                        ))
+                        .unwrap(),
+                    (Some(_), None) => os_input.send_to_client(
+                        client_id,
+                        ServerToClientMsg::ActionResult(Err("there is no such pane".to_string())),
+                    ),
+                    (None, _) => os_input.send_to_client(
+                        client_id,
+                        ServerToClientMsg::ActionResult(Err("the session is closing".to_string())),
+                    ),
+                }
+            }
+            ClientToServerMsg::KillSession => {
+                to_server.send(ServerInstruction::ClientExit).unwrap();
+            }
+            ClientToServerMsg::ClientExited => {
+                // clients that only queried the session were never attached to it
+                os_input.remove_client_sender(client_id);
+                // the server might already be gone if this was the last client
+                let _ = to_server.send(ServerInstruction::RemoveClient(client_id));
+                break;
+            }
         }
     }
 }
+
+#[cfg(test)]
+#[path = "./unit/route_tests.rs"]
+mod route_tests;
@@ -1,7 +1,9 @@
 //! Things related to [`Screen`]s.
 
 use std::collections::BTreeMap;
+use std::fs;
 use std::os::unix::io::RawFd;
+use std::path::{Path, PathBuf};
 use std::str;
 use std::sync::{Arc, RwLock};
 
@@ -10,17 +12,22 @@ use zellij_utils::zellij_tile;
 use crate::{
     panes::PaneId,
     pty::{PtyInstruction, VteBytes},
-    tab::Tab,
+    tab::{Pane, Tab},
     thread_bus::Bus,
-    ui::layout::Layout,
     wasm_vm::PluginInstruction,
     ServerInstruction, SessionState,
 };
 use zellij_tile::data::{Event, InputMode, ModeInfo, Palette, PluginCapabilities, TabInfo};
 use zellij_utils::{
     errors::{ContextType, ScreenContext},
-    input::options::Options,
-    ipc::ClientAttributes,
+    input::{
+        actions::{Direction, LayoutPreset, PaneSize},
+        command::{RunCommand, TerminalAction},
+        layout::{Layout, SessionLayout},
+        options::Options,
+    },
+    ipc::{ClientAttributes, ClientId, ServerToClientMsg, SessionInfo, SessionTree, TabNode},
+    logging::debug_log_to_file,
     pane_size::PositionAndSize,
 };
 
@@ -29,6 +36,7 @@ use zellij_utils::{
 pub(crate) enum ScreenInstruction {
     PtyBytes(RawFd, VteBytes),
     Render,
+    NewPaneFromFocus(Option<Direction>),
     NewPane(PaneId),
     HorizontalSplit(PaneId),
     VerticalSplit(PaneId),
@@ -54,15 +62,39 @@ pub(crate) enum ScreenInstruction {
     ClearScroll,
     CloseFocusedPane,
     ToggleActiveTerminalFullscreen,
+    SwapPane(Direction),
+    MovePane(Direction),
+    RotatePanes(bool),
+    BreakPane,
+    JoinPaneToTab(u32),
+    MoveTabLeft,
+    MoveTabRight,
+    MoveTabToPosition(u32),
+    NextLayout,
+    SelectLayout(LayoutPreset),
+    EqualizePanes,
+    SetPaneWidth(PaneSize),
+    SetPaneHeight(PaneSize),
+    // the client to tell whether the layout was saved, if one asked for it
+    DumpLayout(PathBuf, Option<ClientId>),
+    QuerySessionInfo(SessionInfo, ClientId),
+    QuerySessionTree(SessionTree, ClientId),
+    CapturePane(Option<PaneId>, bool, bool, ClientId),
+    WriteToTerminal(RawFd, Vec<u8>, ClientId),
+    SetPaneName(PaneId, String),
     SetSelectable(PaneId, bool),
     SetMaxHeight(PaneId, usize),
     SetInvisibleBorders(PaneId, bool),
     ClosePane(PaneId),
-    ApplyLayout(Layout, Vec<RawFd>),
+    ApplyLayout(Layout, Vec<RawFd>, String),
+    NewTabForBrokenPane(Layout, Vec<RawFd>),
+    NewTabFromFocus,
     NewTab(RawFd),
     SwitchTabNext,
     SwitchTabPrev,
     ToggleActiveSyncTab,
+    JoinSyncGroup(String),
+    LeaveSyncGroup,
     CloseTab,
     GoToTab(u32),
     UpdateTabName(Vec<u8>),
@@ -75,6 +107,7 @@ impl From<&ScreenInstruction> for ScreenContext {
         match *screen_instruction {
             ScreenInstruction::PtyBytes(..) => ScreenContext::HandlePtyBytes,
             ScreenInstruction::Render => ScreenContext::Render,
+            ScreenInstruction::NewPaneFromFocus(_) => ScreenContext::NewPaneFromFocus,
             ScreenInstruction::NewPane(_) => ScreenContext::NewPane,
             ScreenInstruction::HorizontalSplit(_) => ScreenContext::HorizontalSplit,
             ScreenInstruction::VerticalSplit(_) => ScreenContext::VerticalSplit,
@@ -104,11 +137,32 @@ impl From<&ScreenInstruction> for ScreenContext {
             ScreenInstruction::ToggleActiveTerminalFullscreen => {
                 ScreenContext::ToggleActiveTerminalFullscreen
             }
+            ScreenInstruction::SwapPane(_) => ScreenContext::SwapPane,
+            ScreenInstruction::MovePane(_) => ScreenContext::MovePane,
+            ScreenInstruction::RotatePanes(_) => ScreenContext::RotatePanes,
+            ScreenInstruction::BreakPane => ScreenContext::BreakPane,
+            ScreenInstruction::JoinPaneToTab(_) => ScreenContext::JoinPaneToTab,
+            ScreenInstruction::MoveTabLeft => ScreenContext::MoveTabLeft,
+            ScreenInstruction::MoveTabRight => ScreenContext::MoveTabRight,
+            ScreenInstruction::MoveTabToPosition(_) => ScreenContext::MoveTabToPosition,
+            ScreenInstruction::NextLayout => ScreenContext::NextLayout,
+            ScreenInstruction::SelectLayout(_) => ScreenContext::SelectLayout,
+            ScreenInstruction::EqualizePanes => ScreenContext::EqualizePanes,
+            ScreenInstruction::SetPaneWidth(_) => ScreenContext::SetPaneWidth,
+            ScreenInstruction::SetPaneHeight(_) => ScreenContext::SetPaneHeight,
+            ScreenInstruction::DumpLayout(..) => ScreenContext::DumpLayout,
+            ScreenInstruction::QuerySessionInfo(..) => ScreenContext::QuerySessionInfo,
+            ScreenInstruction::QuerySessionTree(..) => ScreenContext::QuerySessionTree,
+            ScreenInstruction::CapturePane(..) => ScreenContext::CapturePane,
+            ScreenInstruction::WriteToTerminal(..) => ScreenContext::WriteToTerminal,
+            ScreenInstruction::SetPaneName(..) => ScreenContext::SetPaneName,
             ScreenInstruction::SetSelectable(..) => ScreenContext::SetSelectable,
             ScreenInstruction::SetInvisibleBorders(..) => ScreenContext::SetInvisibleBorders,
             ScreenInstruction::SetMaxHeight(..) => ScreenContext::SetMaxHeight,
             ScreenInstruction::ClosePane(_) => ScreenContext::ClosePane,
             ScreenInstruction::ApplyLayout(..) => ScreenContext::ApplyLayout,
+            ScreenInstruction::NewTabForBrokenPane(..) => ScreenContext::NewTabForBrokenPane,
+            ScreenInstruction::NewTabFromFocus => ScreenContext::NewTabFromFocus,
             ScreenInstruction::NewTab(_) => ScreenContext::NewTab,
             ScreenInstruction::SwitchTabNext => ScreenContext::SwitchTabNext,
             ScreenInstruction::SwitchTabPrev => ScreenContext::SwitchTabPrev,
@@ -118,6 +172,8 @@ impl From<&ScreenInstruction> for ScreenContext {
             ScreenInstruction::TerminalResize(_) => ScreenContext::TerminalResize,
             ScreenInstruction::ChangeMode(_) => ScreenContext::ChangeMode,
             ScreenInstruction::ToggleActiveSyncTab => ScreenContext::ToggleActiveSyncTab,
+            ScreenInstruction::JoinSyncGroup(_) => ScreenContext::JoinSyncGroup,
+            ScreenInstruction::LeaveSyncGroup => ScreenContext::LeaveSyncGroup,
         }
     }
 }
@@ -139,6 +195,10 @@ pub(crate) struct Screen {
     input_mode: InputMode,
     colors: Palette,
     session_state: Arc<RwLock<SessionState>>,
+    /// A pane broken out of its tab, waiting for the pty to spawn the rest of its new tab.
+    broken_pane: Option<Box<dyn Pane>>,
+    /// The layout of the tabs opened after those the session started with, saved along with them.
+    layout_template: Option<Layout>,
 }
 
 impl Screen {
@@ -150,6 +210,7 @@ impl Screen {
         mode_info: ModeInfo,
         input_mode: InputMode,
         session_state: Arc<RwLock<SessionState>>,
+        layout_template: Option<Layout>,
     ) -> Self {
         Screen {
             bus,
@@ -161,6 +222,8 @@ impl Screen {
             mode_info,
             input_mode,
             session_state,
+            broken_pane: None,
+            layout_template,
         }
     }
 
@@ -248,15 +311,70 @@ impl Screen {
         }
     }
 
+    /// Moves this [`Screen`]'s active [`Tab`] one position to the left, wrapping around
+    /// to the end if it is the first tab.
+    pub fn move_active_tab_left(&mut self) {
+        let active_tab_pos = self.get_active_tab().unwrap().position;
+        let new_tab_pos = if active_tab_pos == 0 {
+            self.tabs.len() - 1
+        } else {
+            active_tab_pos - 1
+        };
+        self.move_active_tab_to_position(new_tab_pos);
+    }
+
+    /// Moves this [`Screen`]'s active [`Tab`] one position to the right, wrapping around
+    /// to the start if it is the last tab.
+    pub fn move_active_tab_right(&mut self) {
+        let active_tab_pos = self.get_active_tab().unwrap().position;
+        let new_tab_pos = (active_tab_pos + 1) % self.tabs.len();
+        self.move_active_tab_to_position(new_tab_pos);
+    }
+
+    /// Moves this [`Screen`]'s active [`Tab`] to `new_tab_pos`, shifting the tabs in between
+    /// to make room for it.
+    pub fn move_active_tab_to_position(&mut self, new_tab_pos: usize) {
+        let new_tab_pos = std::cmp::min(new_tab_pos, self.tabs.len() - 1);
+        let active_tab = self.get_active_tab().unwrap();
+        let active_tab_index = active_tab.index;
+        let active_tab_pos = active_tab.position;
+        if new_tab_pos == active_tab_pos {
+            return;
+        }
+        for tab in self.tabs.values_mut() {
+            if tab.index == active_tab_index {
+                tab.position = new_tab_pos;
+            } else if active_tab_pos < new_tab_pos
+                && tab.position > active_tab_pos
+                && tab.position <= new_tab_pos
+            {
+                tab.position -= 1;
+            } else if new_tab_pos < active_tab_pos
+                && tab.position >= new_tab_pos
+                && tab.position < active_tab_pos
+            {
+                tab.position += 1;
+            }
+        }
+        self.update_tabs();
+        self.render();
+    }
+
     /// Closes this [`Screen`]'s active [`Tab`], exiting the application if it happens
     /// to be the last tab.
     pub fn close_tab(&mut self) {
         let active_tab_index = self.active_tab_index.unwrap();
-        if self.tabs.len() > 1 {
+        self.close_tab_at_index(active_tab_index);
+    }
+
+    /// Closes the [`Tab`] with the given index, switching to the previous tab if it is the
+    /// active one.
+    fn close_tab_at_index(&mut self, tab_index: usize) {
+        if self.tabs.len() > 1 && self.active_tab_index == Some(tab_index) {
             self.switch_tab_prev();
         }
-        let active_tab = self.tabs.remove(&active_tab_index).unwrap();
-        let pane_ids = active_tab.get_pane_ids();
+        let closed_tab = self.tabs.remove(&tab_index).unwrap();
+        let pane_ids = closed_tab.get_pane_ids();
         // below we don't check the result of sending the CloseTab instruction to the pty thread
         // because this might be happening when the app is closing, at which point the pty thread
         // has already closed and this would result in an error
@@ -274,7 +392,7 @@ impl Screen {
             }
         } else {
             for t in self.tabs.values_mut() {
-                if t.position > active_tab.position {
+                if t.position > closed_tab.position {
                     t.position -= 1;
                 }
             }
@@ -282,6 +400,80 @@ impl Screen {
         }
     }
 
+    /// Takes the focused pane out of the active [`Tab`] and asks the pty thread to open a new
+    /// tab for it, the same way as [`PtyInstruction::NewTab`] does. Returns `false` if there
+    /// is no pane to break out of its tab.
+    pub fn break_active_pane(&mut self) -> bool {
+        let active_tab = self.get_active_tab_mut().unwrap();
+        if active_tab.selectable_pane_count() < 2 {
+            // the pane is already alone in its tab
+            return false;
+        }
+        let pane = match active_tab.extract_active_pane() {
+            Some(pane) => pane,
+            None => return false,
+        };
+        active_tab.set_force_render();
+        // like in `Tab::active_pane_cwd`, a deleted directory is left out
+        let cwd = pane.cwd().filter(|cwd| cwd.is_dir());
+        self.broken_pane = Some(pane);
+        self.bus
+            .senders
+            .send_to_pty(PtyInstruction::BreakPane(cwd))
+            .unwrap();
+        true
+    }
+
+    /// Creates a new [`Tab`] from `layout` for the pane taken out by [`Self::break_active_pane`],
+    /// which takes the first terminal slot of the layout.
+    pub fn new_tab_for_broken_pane(&mut self, layout: Layout, new_pids: Vec<RawFd>) {
+        match self.broken_pane.take() {
+            Some(pane) => self.new_tab_from_layout(layout, new_pids, String::new(), Some(pane)),
+            None => {
+                for pid in new_pids {
+                    self.bus
+                        .senders
+                        .send_to_pty(PtyInstruction::ClosePane(PaneId::Terminal(pid)))
+                        .unwrap();
+                }
+            }
+        }
+    }
+
+    /// Moves the focused pane of the active [`Tab`] into the tab at `tab_position` (counting
+    /// from 1) and switches to it. The original tab is closed if the pane was the last one in it.
+    pub fn join_active_pane_to_tab(&mut self, tab_position: usize) {
+        let active_tab_index = self.active_tab_index.unwrap();
+        let target_tab_index = match self
+            .tabs
+            .values()
+            .find(|t| t.position + 1 == tab_position && t.index != active_tab_index)
+        {
+            Some(target_tab) if target_tab.has_room_for_new_pane() => target_tab.index,
+            _ => return,
+        };
+        let pane = match self.get_active_tab_mut().unwrap().extract_active_pane() {
+            Some(pane) => pane,
+            None => return,
+        };
+        let target_tab = self.tabs.get_mut(&target_tab_index).unwrap();
+        target_tab.add_existing_pane(pane);
+        target_tab.set_force_render();
+        self.active_tab_index = Some(target_tab_index);
+        let source_tab_is_empty = self
+            .tabs
+            .get(&active_tab_index)
+            .unwrap()
+            .get_active_pane()
+            .is_none();
+        if source_tab_is_empty {
+            self.close_tab_at_index(active_tab_index);
+        } else {
+            self.update_tabs();
+        }
+        self.render();
+    }
+
     pub fn resize_to_screen(&mut self, new_screen_size: PositionAndSize) {
         self.position_and_size = new_screen_size;
         for (_, tab) in self.tabs.iter_mut() {
@@ -318,6 +510,19 @@ impl Screen {
         }
     }
 
+    /// Writes to the panes in the sync group `sync_group`, in all tabs.
+    pub fn write_to_sync_group(&mut self, sync_group: &str, input_bytes: Vec<u8>) {
+        for tab in self.tabs.values_mut() {
+            tab.write_to_sync_group(sync_group, input_bytes.clone());
+        }
+    }
+
+    /// Returns the working directory of the focused pane of the active [`Tab`], if known.
+    pub fn active_pane_cwd(&self) -> Option<PathBuf> {
+        self.get_active_tab()
+            .and_then(|active_tab| active_tab.active_pane_cwd())
+    }
+
     /// Returns a mutable reference to this [`Screen`]'s active [`Tab`].
     pub fn get_active_tab_mut(&mut self) -> Option<&mut Tab> {
         match self.active_tab_index {
@@ -326,15 +531,25 @@ impl Screen {
         }
     }
 
-    /// Creates a new [`Tab`] in this [`Screen`], applying the specified [`Layout`]
-    /// and switching to it.
-    pub fn apply_layout(&mut self, layout: Layout, new_pids: Vec<RawFd>) {
+    /// Creates a new [`Tab`] named `tab_name` in this [`Screen`], applying the specified
+    /// [`Layout`] and switching to it.
+    pub fn apply_layout(&mut self, layout: Layout, new_pids: Vec<RawFd>, tab_name: String) {
+        self.new_tab_from_layout(layout, new_pids, tab_name, None);
+    }
+
+    fn new_tab_from_layout(
+        &mut self,
+        layout: Layout,
+        new_pids: Vec<RawFd>,
+        tab_name: String,
+        existing_pane: Option<Box<dyn Pane>>,
+    ) {
         let tab_index = self.get_new_tab_index();
         let position = self.tabs.len();
         let mut tab = Tab::new(
             tab_index,
             position,
-            String::new(),
+            tab_name,
             &self.position_and_size,
             self.bus.os_input.as_ref().unwrap().clone(),
             self.bus.senders.clone(),
@@ -345,16 +560,102 @@ impl Screen {
             self.colors,
             self.session_state.clone(),
         );
-        tab.apply_layout(layout, new_pids);
+        tab.apply_layout(layout, new_pids, existing_pane);
         self.active_tab_index = Some(tab_index);
         self.tabs.insert(tab_index, tab);
         self.update_tabs();
     }
 
+    /// Saves the tabs of this [`Screen`] to `path` as a [`SessionLayout`] that recreates them.
+    /// Fails if a tab cannot be described by a layout, rather than leaving it out.
+    pub fn dump_layout(&self, path: &Path) -> Result<(), String> {
+        if self.tabs.is_empty() {
+            return Err("the session is closing".to_string());
+        }
+        let active_tab_index = self.active_tab_index;
+        let mut tabs: Vec<&Tab> = self.tabs.values().collect();
+        tabs.sort_by_key(|tab| tab.position);
+        let tabs = tabs
+            .into_iter()
+            .map(|tab| {
+                let mut tab_layout = tab.dump_layout().ok_or_else(|| {
+                    format!(
+                        "tab {} cannot be saved as a layout: its panes overlap or are hidden",
+                        tab.position + 1
+                    )
+                })?;
+                if Some(tab.index) == active_tab_index {
+                    tab_layout.focus = Some(true);
+                }
+                Ok(tab_layout)
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        let session_layout = SessionLayout {
+            tabs,
+            template: self.layout_template.clone(),
+        };
+        let yaml = session_layout.to_yaml().map_err(|e| e.to_string())?;
+        fs::write(path, yaml).map_err(|e| format!("could not write {}: {}", path.display(), e))
+    }
+
+    /// Writes the bytes to the terminal pane with the given id, in whichever tab it is, and
+    /// returns whether there is such a pane.
+    pub fn write_to_terminal(&mut self, pid: RawFd, bytes: Vec<u8>) -> bool {
+        match self.tabs.values_mut().find(|tab| tab.has_terminal_pid(pid)) {
+            Some(tab) => {
+                tab.write_to_pane_id(bytes, PaneId::Terminal(pid));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the contents of the pane with the given id, or of the focused pane.
+    pub fn capture_pane(
+        &self,
+        pane_id: Option<PaneId>,
+        with_scrollback: bool,
+        with_ansi: bool,
+    ) -> Option<String> {
+        match pane_id {
+            Some(pane_id) => self
+                .tabs
+                .values()
+                .find_map(|tab| tab.capture_pane(Some(pane_id), with_scrollback, with_ansi)),
+            None => self
+                .get_active_tab()
+                .and_then(|tab| tab.capture_pane(None, with_scrollback, with_ansi)),
+        }
+    }
+
+    /// Describes the tabs and their panes, in the order the tabs are shown.
+    pub fn tab_nodes(&self) -> Vec<TabNode> {
+        let mut tabs: Vec<&Tab> = self.tabs.values().collect();
+        tabs.sort_by_key(|tab| tab.position);
+        tabs.into_iter()
+            .map(|tab| TabNode {
+                position: tab.position,
+                name: tab.name.clone(),
+                focused: self.active_tab_index == Some(tab.index),
+                panes: tab.pane_nodes(),
+            })
+            .collect()
+    }
+
+    /// Returns the names of the tabs, in the order they are shown.
+    pub fn tab_names(&self) -> Vec<String> {
+        let mut tabs: Vec<&Tab> = self.tabs.values().collect();
+        tabs.sort_by_key(|tab| tab.position);
+        tabs.into_iter().map(|tab| tab.name.clone()).collect()
+    }
+
     pub fn update_tabs(&self) {
         let mut tab_data = vec![];
         let active_tab_index = self.active_tab_index.unwrap();
-        for tab in self.tabs.values() {
+        // tabs can be moved around, so their order is not necessarily that of their indices
+        let mut tabs: Vec<&Tab> = self.tabs.values().collect();
+        tabs.sort_by_key(|tab| tab.position);
+        for tab in tabs {
             tab_data.push(TabInfo {
                 position: tab.position,
                 name: tab.name.clone(),
@@ -403,6 +704,7 @@ pub(crate) fn screen_thread_main(
     client_attributes: ClientAttributes,
     config_options: Box<Options>,
     session_state: Arc<RwLock<SessionState>>,
+    layout_template: Option<Layout>,
 ) {
     let capabilities = config_options.simplified_ui;
 
@@ -419,6 +721,7 @@ pub(crate) fn screen_thread_main(
         },
         InputMode::Normal,
         session_state,
+        layout_template,
     );
     loop {
         let (event, mut err_ctx) = screen
@@ -448,6 +751,26 @@ pub(crate) fn screen_thread_main(
             ScreenInstruction::Render => {
                 screen.render();
             }
+            ScreenInstruction::NewPaneFromFocus(direction) => {
+                // the new pane starts in the directory of the focused one
+                let terminal_action = screen.active_pane_cwd().map(|cwd| {
+                    TerminalAction::RunCommand(RunCommand {
+                        cwd: Some(cwd),
+                        ..Default::default()
+                    })
+                });
+                let pty_instruction = match direction {
+                    Some(Direction::Left) | Some(Direction::Right) => {
+                        PtyInstruction::SpawnTerminalVertically(terminal_action)
+                    }
+                    Some(Direction::Up) | Some(Direction::Down) => {
+                        PtyInstruction::SpawnTerminalHorizontally(terminal_action)
+                    }
+                    // No direction specified - try to put it in the biggest available spot
+                    None => PtyInstruction::SpawnTerminal(terminal_action),
+                };
+                screen.bus.senders.send_to_pty(pty_instruction).unwrap();
+            }
             ScreenInstruction::NewPane(pid) => {
                 screen.get_active_tab_mut().unwrap().new_pane(pid);
                 screen
@@ -474,9 +797,12 @@ pub(crate) fn screen_thread_main(
             }
             ScreenInstruction::WriteCharacter(bytes) => {
                 let active_tab = screen.get_active_tab_mut().unwrap();
-                match active_tab.is_sync_panes_active() {
-                    true => active_tab.write_to_terminals_on_current_tab(bytes),
-                    false => active_tab.write_to_active_terminal(bytes),
+                if active_tab.is_sync_panes_active() {
+                    active_tab.write_to_terminals_on_current_tab(bytes);
+                } else if let Some(sync_group) = active_tab.active_pane_sync_group() {
+                    screen.write_to_sync_group(&sync_group, bytes);
+                } else {
+                    active_tab.write_to_active_terminal(bytes);
                 }
             }
             ScreenInstruction::ResizeLeft => {
@@ -595,6 +921,107 @@ pub(crate) fn screen_thread_main(
                     .unwrap()
                     .toggle_active_pane_fullscreen();
             }
+            ScreenInstruction::SwapPane(direction) => {
+                screen
+                    .get_active_tab_mut()
+                    .unwrap()
+                    .swap_active_pane(direction);
+            }
+            ScreenInstruction::MovePane(direction) => {
+                screen
+                    .get_active_tab_mut()
+                    .unwrap()
+                    .move_active_pane(direction);
+            }
+            ScreenInstruction::RotatePanes(backwards) => {
+                screen.get_active_tab_mut().unwrap().rotate_panes(backwards);
+            }
+            ScreenInstruction::NextLayout => {
+                screen.get_active_tab_mut().unwrap().next_layout_preset();
+            }
+            ScreenInstruction::SelectLayout(preset) => {
+                screen
+                    .get_active_tab_mut()
+                    .unwrap()
+                    .select_layout_preset(preset);
+            }
+            ScreenInstruction::EqualizePanes => {
+                screen.get_active_tab_mut().unwrap().equalize_panes();
+            }
+            ScreenInstruction::SetPaneWidth(width) => {
+                screen
+                    .get_active_tab_mut()
+                    .unwrap()
+                    .set_active_pane_width(width);
+            }
+            ScreenInstruction::SetPaneHeight(height) => {
+                screen
+                    .get_active_tab_mut()
+                    .unwrap()
+                    .set_active_pane_height(height);
+            }
+            ScreenInstruction::DumpLayout(path, client_id) => {
+                let saved = screen.dump_layout(&path);
+                match client_id {
+                    Some(client_id) => screen
+                        .bus
+                        .os_input
+                        .as_ref()
+                        .unwrap()
+                        .send_to_client(client_id, ServerToClientMsg::ActionResult(saved)),
+                    None => {
+                        if let Err(e) = saved {
+                            let _ = debug_log_to_file(format!(
+                                "failed to save the layout to {}: {}",
+                                path.display(),
+                                e
+                            ));
+                        }
+                    }
+                }
+            }
+            ScreenInstruction::QuerySessionInfo(mut session_info, client_id) => {
+                session_info.tabs = screen.tab_names();
+                screen
+                    .bus
+                    .os_input
+                    .as_ref()
+                    .unwrap()
+                    .send_to_client(client_id, ServerToClientMsg::SessionInfo(session_info));
+            }
+            ScreenInstruction::BreakPane => {
+                if !screen.break_active_pane() {
+                    screen
+                        .bus
+                        .senders
+                        .send_to_server(ServerInstruction::UnblockInputThread)
+                        .unwrap();
+                }
+            }
+            ScreenInstruction::NewTabForBrokenPane(layout, new_pane_pids) => {
+                screen.new_tab_for_broken_pane(layout, new_pane_pids);
+                screen
+                    .bus
+                    .senders
+                    .send_to_server(ServerInstruction::UnblockInputThread)
+                    .unwrap();
+            }
+            ScreenInstruction::JoinPaneToTab(tab_position) => {
+                screen.join_active_pane_to_tab(tab_position as usize);
+                screen
+                    .bus
+                    .senders
+                    .send_to_server(ServerInstruction::UnblockInputThread)
+                    .unwrap();
+            }
+            ScreenInstruction::NewTabFromFocus => {
+                let cwd = screen.active_pane_cwd();
+                screen
+                    .bus
+                    .senders
+                    .send_to_pty(PtyInstruction::NewTab(cwd))
+                    .unwrap();
+            }
             ScreenInstruction::NewTab(pane_id) => {
                 screen.new_tab(pane_id);
                 screen
@@ -627,8 +1054,8 @@ pub(crate) fn screen_thread_main(
                     .send_to_server(ServerInstruction::UnblockInputThread)
                     .unwrap();
             }
-            ScreenInstruction::ApplyLayout(layout, new_pane_pids) => {
-                screen.apply_layout(layout, new_pane_pids);
+            ScreenInstruction::ApplyLayout(layout, new_pane_pids, tab_name) => {
+                screen.apply_layout(layout, new_pane_pids, tab_name);
                 screen
                     .bus
                     .senders
@@ -643,6 +1070,18 @@ pub(crate) fn screen_thread_main(
                     .send_to_server(ServerInstruction::UnblockInputThread)
                     .unwrap();
             }
+            ScreenInstruction::MoveTabLeft => {
+                screen.move_active_tab_left();
+            }
+            ScreenInstruction::MoveTabRight => {
+                screen.move_active_tab_right();
+            }
+            ScreenInstruction::MoveTabToPosition(tab_position) => {
+                // positions are counted from 1 on the user facing side, like in `GoToTab`, so
+                // position 0 is taken as the first position too
+                let tab_position = std::cmp::max(tab_position, 1) as usize;
+                screen.move_active_tab_to_position(tab_position - 1);
+            }
             ScreenInstruction::UpdateTabName(c) => {
                 screen.update_active_tab_name(c);
             }
@@ -659,6 +1098,54 @@ pub(crate) fn screen_thread_main(
                     .toggle_sync_panes_is_active();
                 screen.update_tabs();
             }
+            ScreenInstruction::JoinSyncGroup(sync_group) => {
+                screen
+                    .get_active_tab_mut()
+                    .unwrap()
+                    .set_active_pane_sync_group(Some(sync_group));
+            }
+            ScreenInstruction::QuerySessionTree(mut session_tree, client_id) => {
+                session_tree.tabs = screen.tab_nodes();
+                screen
+                    .bus
+                    .os_input
+                    .as_ref()
+                    .unwrap()
+                    .send_to_client(client_id, ServerToClientMsg::SessionTree(session_tree));
+            }
+            ScreenInstruction::CapturePane(pane_id, with_scrollback, with_ansi, client_id) => {
+                let contents = screen.capture_pane(pane_id, with_scrollback, with_ansi);
+                screen
+                    .bus
+                    .os_input
+                    .as_ref()
+                    .unwrap()
+                    .send_to_client(client_id, ServerToClientMsg::PaneContents(contents));
+            }
+            ScreenInstruction::WriteToTerminal(pid, bytes, client_id) => {
+                let written = if screen.write_to_terminal(pid, bytes) {
+                    Ok(())
+                } else {
+                    Err("the pane was closed".to_string())
+                };
+                screen
+                    .bus
+                    .os_input
+                    .as_ref()
+                    .unwrap()
+                    .send_to_client(client_id, ServerToClientMsg::ActionResult(written));
+            }
+            ScreenInstruction::SetPaneName(pane_id, name) => {
+                for tab in screen.get_tabs_mut().values_mut() {
+                    tab.set_pane_name(pane_id, name.clone());
+                }
+            }
+            ScreenInstruction::LeaveSyncGroup => {
+                screen
+                    .get_active_tab_mut()
+                    .unwrap()
+                    .set_active_pane_sync_group(None);
+            }
             ScreenInstruction::Exit => {
                 break;
             }
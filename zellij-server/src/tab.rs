@@ -8,20 +8,33 @@ use crate::{
     panes::{PaneId, PluginPane, TerminalPane},
     pty::{PtyInstruction, VteBytes},
     thread_bus::ThreadSenders,
-    ui::{boundaries::Boundaries, layout::Layout, pane_resizer::PaneResizer},
+    ui::{boundaries::Boundaries, pane_resizer::PaneResizer},
     wasm_vm::PluginInstruction,
     ServerInstruction, SessionState,
 };
 use serde::{Deserialize, Serialize};
 use std::os::unix::io::RawFd;
+use std::path::{Path, PathBuf};
 use std::sync::{mpsc::channel, Arc, RwLock};
 use std::time::Instant;
 use std::{
     cmp::Reverse,
     collections::{BTreeMap, HashSet},
+    convert::TryFrom,
+};
+use zellij_tile::data::{Event, InputMode, ModeInfo, Palette, PaletteColor};
+use zellij_utils::{
+    input::{
+        actions::{Direction, LayoutPreset, PaneSize},
+        command::RunCommand,
+        layout::{Direction as LayoutDirection, Layout, SplitSize, TabLayout},
+        parse_keys,
+    },
+    ipc::{PaneKind, PaneNode},
+    logging::debug_log_to_file,
+    pane_size::PositionAndSize,
+    shared::adjust_to_size,
 };
-use zellij_tile::data::{Event, InputMode, ModeInfo, Palette};
-use zellij_utils::{input::parse_keys, pane_size::PositionAndSize, shared::adjust_to_size};
 
 const CURSOR_HEIGHT_WIDTH_RATIO: usize = 4; // this is not accurate and kind of a magic number, TODO: look into this
 
@@ -32,6 +45,32 @@ const MIN_TERMINAL_WIDTH: usize = 4;
 
 type BorderAndPaneIds = (usize, Vec<PaneId>);
 
+/// The shell started in new panes is what a layout runs by default, so only the directory it
+/// was in needs to be kept.
+fn without_default_shell(run: RunCommand) -> RunCommand {
+    let is_shell = |command: &Path| {
+        let file_name = command
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string());
+        let shell = std::env::var("SHELL").ok();
+        let shell_name = shell
+            .as_ref()
+            .and_then(|shell| Path::new(shell).file_name())
+            .map(|name| name.to_string_lossy().to_string());
+        // login shells are started with a leading dash
+        file_name
+            .map(|name| name.starts_with('-') || Some(name) == shell_name)
+            .unwrap_or(false)
+    };
+    match run.command {
+        Some(ref command) if !is_shell(command) => run,
+        _ => RunCommand {
+            cwd: run.cwd,
+            ..Default::default()
+        },
+    }
+}
+
 fn split_vertically_with_gap(rect: &PositionAndSize) -> (PositionAndSize, PositionAndSize) {
     let width_of_each_half = (rect.columns - 1) / 2;
     let mut first_rect = *rect;
@@ -73,6 +112,7 @@ pub(crate) struct Tab {
     os_api: Box<dyn ServerOsApi>,
     pub senders: ThreadSenders,
     synchronize_is_active: bool,
+    layout_preset: Option<LayoutPreset>,
     should_clear_display_before_rendering: bool,
     session_state: Arc<RwLock<SessionState>>,
     pub mode_info: ModeInfo,
@@ -100,12 +140,16 @@ pub trait Pane {
     fn columns(&self) -> usize;
     fn reset_size_and_position_override(&mut self);
     fn change_pos_and_size(&mut self, position_and_size: &PositionAndSize);
+    /// Moves the pane to the position of `position_and_size`, and gives it its size.
+    fn relocate(&mut self, position_and_size: &PositionAndSize);
     fn override_size_and_position(&mut self, x: usize, y: usize, size: &PositionAndSize);
     fn handle_pty_bytes(&mut self, bytes: VteBytes);
     fn cursor_coordinates(&self) -> Option<(usize, usize)>;
     fn adjust_input_to_terminal(&self, input_bytes: Vec<u8>) -> Vec<u8>;
 
     fn position_and_size_override(&self) -> Option<PositionAndSize>;
+    /// The position and size of this pane, ignoring any override (eg. when it is fullscreen).
+    fn base_position_and_size(&self) -> PositionAndSize;
     fn should_render(&self) -> bool;
     fn set_should_render(&mut self, should_render: bool);
     fn selectable(&self) -> bool;
@@ -215,6 +259,23 @@ pub trait Pane {
     fn max_height(&self) -> Option<usize> {
         None
     }
+    fn plugin_path(&self) -> Option<PathBuf> {
+        None
+    }
+    fn cwd(&self) -> Option<PathBuf> {
+        None
+    }
+    fn sync_group(&self) -> Option<String> {
+        None
+    }
+    fn set_sync_group(&mut self, _sync_group: Option<String>) {}
+    fn set_name(&mut self, _name: String) {}
+    fn title(&self) -> Option<String> {
+        None
+    }
+    fn capture(&self, _with_scrollback: bool, _with_ansi: bool) -> Option<String> {
+        None
+    }
     fn invisible_borders(&self) -> bool {
         false
     }
@@ -225,6 +286,20 @@ pub trait Pane {
     }
 }
 
+/// The color framing the panes of `sync_group`, the same in every tab.
+fn sync_group_color(sync_group: &str, colors: Palette) -> PaletteColor {
+    let group_colors = [
+        colors.magenta,
+        colors.cyan,
+        colors.yellow,
+        colors.blue,
+        colors.orange,
+        colors.red,
+    ];
+    let index = sync_group.bytes().map(usize::from).sum::<usize>() % group_colors.len();
+    group_colors[index]
+}
+
 impl Tab {
     // FIXME: Still too many arguments for clippy to be happy...
     #[allow(clippy::too_many_arguments)]
@@ -266,6 +341,7 @@ impl Tab {
             full_screen_ws: *full_screen_ws,
             fullscreen_is_active: false,
             synchronize_is_active: false,
+            layout_preset: None,
             os_api,
             senders,
             should_clear_display_before_rendering: false,
@@ -276,7 +352,14 @@ impl Tab {
         }
     }
 
-    pub fn apply_layout(&mut self, layout: Layout, new_pids: Vec<RawFd>) {
+    /// Fills this tab with the panes of `layout`. If there is an `existing_pane`, it takes the
+    /// first terminal slot of the layout instead of one of `new_pids`, and gets the focus.
+    pub fn apply_layout(
+        &mut self,
+        layout: Layout,
+        new_pids: Vec<RawFd>,
+        mut existing_pane: Option<Box<dyn Pane>>,
+    ) {
         // TODO: this should be an attribute on Screen instead of full_screen_ws
         let free_space = PositionAndSize {
             x: 0,
@@ -286,7 +369,17 @@ impl Tab {
             ..Default::default()
         };
         self.panes_to_hide.clear();
-        let positions_in_layout = layout.position_panes_in_space(&free_space);
+        let positions_in_layout = match layout.position_panes_in_space(&free_space) {
+            Ok(positions_in_layout) => positions_in_layout,
+            Err(e) => {
+                // the layout does not fit on the screen, open a single pane instead (the pids
+                // left over are closed below)
+                let _ = debug_log_to_file(format!("could not apply layout: {}", e));
+                Layout::from_preset(LayoutPreset::EvenHorizontal, 1)
+                    .position_panes_in_space(&free_space)
+                    .unwrap_or_default()
+            }
+        };
         let mut positions_and_size = positions_in_layout.iter();
         for (pane_kind, terminal_pane) in self.panes.iter_mut() {
             // for now the layout only supports terminal panes
@@ -316,6 +409,8 @@ impl Tab {
             }
         }
         let mut new_pids = new_pids.iter();
+        let mut focused_pane_id = None;
+        let existing_pane_id = existing_pane.as_ref().map(|pane| pane.pid());
         for (layout, position_and_size) in positions_and_size {
             // Just a regular terminal
             if let Some(plugin) = &layout.plugin {
@@ -328,6 +423,7 @@ impl Tab {
                     pid,
                     *position_and_size,
                     self.senders.to_plugin.as_ref().unwrap().clone(),
+                    plugin.clone(),
                 );
                 if let Some(max_rows) = position_and_size.max_rows {
                     new_plugin.set_max_height(max_rows);
@@ -336,6 +432,9 @@ impl Tab {
                     new_plugin.set_max_width(max_columns);
                 }
                 self.panes.insert(PaneId::Plugin(pid), Box::new(new_plugin));
+                if layout.focus == Some(true) {
+                    focused_pane_id = Some(PaneId::Plugin(pid));
+                }
                 // Send an initial mode update to the newly loaded plugin only!
                 self.senders
                     .send_to_plugin(PluginInstruction::Update(
@@ -343,6 +442,17 @@ impl Tab {
                         Event::ModeUpdate(self.mode_info.clone()),
                     ))
                     .unwrap();
+            } else if let Some(mut pane) = existing_pane.take() {
+                let pane_id = pane.pid();
+                pane.reset_size_and_position_override();
+                if let Some(max_rows) = position_and_size.max_rows {
+                    pane.set_max_height(max_rows);
+                }
+                if let Some(max_columns) = position_and_size.max_columns {
+                    pane.set_max_width(max_columns);
+                }
+                self.panes.insert(pane_id, pane);
+                self.set_pane_position_and_size(pane_id, *position_and_size);
             } else {
                 // there are still panes left to fill, use the pids we received in this method
                 let pid = new_pids.next().unwrap(); // if this crashes it means we got less pids than there are panes in this layout
@@ -354,6 +464,9 @@ impl Tab {
                 );
                 self.panes
                     .insert(PaneId::Terminal(*pid), Box::new(new_terminal));
+                if layout.focus == Some(true) {
+                    focused_pane_id = Some(PaneId::Terminal(*pid));
+                }
             }
         }
         for unused_pid in new_pids {
@@ -364,7 +477,13 @@ impl Tab {
                 .send_to_pty(PtyInstruction::ClosePane(PaneId::Terminal(*unused_pid)))
                 .unwrap();
         }
-        self.active_terminal = self.panes.iter().map(|(id, _)| id.to_owned()).next();
+        if let Some(pane) = existing_pane {
+            // the layout has no terminal slot for it
+            self.add_existing_pane(pane);
+        }
+        self.active_terminal = existing_pane_id
+            .or(focused_pane_id)
+            .or_else(|| self.panes.iter().map(|(id, _)| id.to_owned()).next());
         self.render();
     }
     pub fn new_pane(&mut self, pid: PaneId) {
@@ -386,24 +505,7 @@ impl Tab {
         } else {
             // TODO: check minimum size of active terminal
 
-            let (_largest_terminal_size, terminal_id_to_split) = self.get_panes().fold(
-                (0, None),
-                |(current_largest_terminal_size, current_terminal_id_to_split),
-                 id_and_terminal_to_check| {
-                    let (id_of_terminal_to_check, terminal_to_check) = id_and_terminal_to_check;
-                    let terminal_size = (terminal_to_check.rows() * CURSOR_HEIGHT_WIDTH_RATIO)
-                        * terminal_to_check.columns();
-                    let terminal_can_be_split = terminal_to_check.columns() >= MIN_TERMINAL_WIDTH
-                        && terminal_to_check.rows() >= MIN_TERMINAL_HEIGHT
-                        && ((terminal_to_check.columns() > terminal_to_check.min_width() * 2)
-                            || (terminal_to_check.rows() > terminal_to_check.min_height() * 2));
-                    if terminal_can_be_split && terminal_size > current_largest_terminal_size {
-                        (terminal_size, Some(*id_of_terminal_to_check))
-                    } else {
-                        (current_largest_terminal_size, current_terminal_id_to_split)
-                    }
-                },
-            );
+            let terminal_id_to_split = self.largest_splittable_pane_id();
             if terminal_id_to_split.is_none() {
                 self.senders
                     .send_to_pty(PtyInstruction::ClosePane(pid)) // we can't open this pane, close the pty
@@ -465,6 +567,78 @@ impl Tab {
             self.render();
         }
     }
+    fn largest_splittable_pane_id(&self) -> Option<PaneId> {
+        let (_largest_terminal_size, terminal_id_to_split) = self.get_panes().fold(
+            (0, None),
+            |(current_largest_terminal_size, current_terminal_id_to_split),
+             id_and_terminal_to_check| {
+                let (id_of_terminal_to_check, terminal_to_check) = id_and_terminal_to_check;
+                let terminal_size = (terminal_to_check.rows() * CURSOR_HEIGHT_WIDTH_RATIO)
+                    * terminal_to_check.columns();
+                let terminal_can_be_split = terminal_to_check.columns() >= MIN_TERMINAL_WIDTH
+                    && terminal_to_check.rows() >= MIN_TERMINAL_HEIGHT
+                    && ((terminal_to_check.columns() > terminal_to_check.min_width() * 2)
+                        || (terminal_to_check.rows() > terminal_to_check.min_height() * 2));
+                if terminal_can_be_split && terminal_size > current_largest_terminal_size {
+                    (terminal_size, Some(*id_of_terminal_to_check))
+                } else {
+                    (current_largest_terminal_size, current_terminal_id_to_split)
+                }
+            },
+        );
+        terminal_id_to_split
+    }
+    pub fn has_room_for_new_pane(&self) -> bool {
+        !self.has_panes() || self.largest_splittable_pane_id().is_some()
+    }
+    // splits the largest pane in this tab, returning the position and size of the space freed up
+    fn split_largest_pane(&mut self) -> Option<PositionAndSize> {
+        let pane_id_to_split = self.largest_splittable_pane_id()?;
+        let pane_to_split = self.panes.get(&pane_id_to_split).unwrap();
+        let pane_ws = pane_to_split.position_and_size();
+        let (remaining_ws, free_ws) = if pane_to_split.rows() * CURSOR_HEIGHT_WIDTH_RATIO
+            > pane_to_split.columns()
+            && pane_to_split.rows() > pane_to_split.min_height() * 2
+        {
+            split_horizontally_with_gap(&pane_ws)
+        } else {
+            split_vertically_with_gap(&pane_ws)
+        };
+        self.set_pane_position_and_size(pane_id_to_split, remaining_ws);
+        Some(free_ws)
+    }
+    pub fn add_existing_pane(&mut self, mut pane: Box<dyn Pane>) {
+        if self.fullscreen_is_active {
+            self.toggle_active_pane_fullscreen();
+        }
+        let position_and_size = if self.has_panes() {
+            self.split_largest_pane()
+        } else {
+            Some(self.full_screen_ws)
+        };
+        if let Some(position_and_size) = position_and_size {
+            let pane_id = pane.pid();
+            pane.reset_size_and_position_override();
+            self.panes.insert(pane_id, pane);
+            self.set_pane_position_and_size(pane_id, position_and_size);
+            self.active_terminal = Some(pane_id);
+        }
+    }
+    pub fn extract_active_pane(&mut self) -> Option<Box<dyn Pane>> {
+        let active_pane_id = self.get_active_pane_id()?;
+        // the last pane leaves nothing behind, as its tab is closed
+        if self.selectable_pane_count() > 1 && !self.pane_space_can_be_taken(active_pane_id) {
+            return None;
+        }
+        let pane = self.close_pane_without_rerender(active_pane_id);
+        if self.active_terminal == Some(active_pane_id) {
+            self.active_terminal = self.next_active_pane(self.get_pane_ids());
+        }
+        pane
+    }
+    pub fn selectable_pane_count(&self) -> usize {
+        self.get_selectable_panes().count()
+    }
     pub fn horizontal_split(&mut self, pid: PaneId) {
         self.close_down_to_max_terminals();
         if self.fullscreen_is_active {
@@ -620,6 +794,89 @@ impl Tab {
             self.write_to_pane_id(input_bytes.clone(), pane_id);
         });
     }
+    /// Writes to every pane of this tab in the sync group `sync_group`.
+    pub fn write_to_sync_group(&mut self, sync_group: &str, input_bytes: Vec<u8>) {
+        let pane_ids: Vec<PaneId> = self
+            .panes
+            .iter()
+            .filter(|(_, pane)| pane.sync_group().as_deref() == Some(sync_group))
+            .map(|(id, _)| *id)
+            .collect();
+        for pane_id in pane_ids {
+            self.write_to_pane_id(input_bytes.clone(), pane_id);
+        }
+    }
+    pub fn active_pane_sync_group(&self) -> Option<String> {
+        self.get_active_pane()?.sync_group()
+    }
+    /// Makes the focused pane a member of `sync_group` (or of none), leaving any other group.
+    pub fn set_active_pane_sync_group(&mut self, sync_group: Option<String>) {
+        if let Some(active_pane_id) = self.get_active_pane_id() {
+            if let Some(active_pane) = self.panes.get_mut(&active_pane_id) {
+                active_pane.set_sync_group(sync_group);
+                self.render();
+            }
+        }
+    }
+    /// Describes the panes of this tab: where they are, and what runs in them.
+    pub fn pane_nodes(&self) -> Vec<PaneNode> {
+        let active_pane_id = self.get_active_pane_id();
+        self.panes
+            .iter()
+            .filter_map(|(pane_id, pane)| {
+                let (id, kind, command) = match pane_id {
+                    PaneId::Terminal(pid) => (
+                        self.os_api.terminal_id(*pid)?,
+                        PaneKind::Terminal,
+                        self.os_api.get_foreground_command(*pid),
+                    ),
+                    PaneId::Plugin(pid) => (*pid, PaneKind::Plugin, None),
+                };
+                Some(PaneNode {
+                    id,
+                    kind,
+                    plugin: pane.plugin_path(),
+                    x: pane.x(),
+                    y: pane.y(),
+                    columns: pane.columns(),
+                    rows: pane.rows(),
+                    selectable: pane.selectable(),
+                    focused: active_pane_id == Some(*pane_id),
+                    title: pane.title(),
+                    cwd: pane
+                        .cwd()
+                        .or_else(|| command.as_ref().and_then(|command| command.cwd.clone())),
+                    command: command
+                        .and_then(|command| {
+                            let program = command.command?.to_string_lossy().to_string();
+                            Some(
+                                std::iter::once(program)
+                                    .chain(command.args)
+                                    .collect::<Vec<_>>(),
+                            )
+                        })
+                        .unwrap_or_default(),
+                })
+            })
+            .collect()
+    }
+    /// Returns the contents of the pane with the given id, or of the active pane.
+    pub fn capture_pane(
+        &self,
+        pane_id: Option<PaneId>,
+        with_scrollback: bool,
+        with_ansi: bool,
+    ) -> Option<String> {
+        let pane_id = pane_id.or_else(|| self.get_active_pane_id())?;
+        self.panes
+            .get(&pane_id)?
+            .capture(with_scrollback, with_ansi)
+    }
+    pub fn set_pane_name(&mut self, pane_id: PaneId, name: String) {
+        if let Some(pane) = self.panes.get_mut(&pane_id) {
+            pane.set_name(name);
+        }
+    }
     pub fn write_to_active_terminal(&mut self, input_bytes: Vec<u8>) {
         self.write_to_pane_id(input_bytes, self.get_active_pane_id().unwrap());
     }
@@ -739,14 +996,23 @@ impl Tab {
             output.push_str(clear_display);
             self.should_clear_display_before_rendering = false;
         }
+        let colors = self.colors;
         for (kind, pane) in self.panes.iter_mut() {
             if !self.panes_to_hide.contains(&pane.pid()) {
-                match self.active_terminal.unwrap() == pane.pid() {
-                    true => {
+                match (
+                    self.active_terminal.unwrap() == pane.pid(),
+                    pane.sync_group(),
+                ) {
+                    (true, _) => {
                         pane.set_active_at(Instant::now());
-                        boundaries.add_rect(pane.as_ref(), self.mode_info.mode, Some(self.colors))
+                        boundaries.add_rect(pane.as_ref(), self.mode_info.mode, Some(colors))
                     }
-                    false => boundaries.add_rect(pane.as_ref(), self.mode_info.mode, None),
+                    // the members of each sync group are framed in the color of the group
+                    (false, Some(sync_group)) => boundaries.add_rect_with_color(
+                        pane.as_ref(),
+                        Some(sync_group_color(&sync_group, colors)),
+                    ),
+                    (false, None) => boundaries.add_rect(pane.as_ref(), self.mode_info.mode, None),
                 }
                 if let Some(vte_output) = pane.render() {
                     let vte_output = if let PaneId::Terminal(_) = kind {
@@ -1985,6 +2251,357 @@ impl Tab {
         }
         false
     }
+    fn pane_id_in_direction(&self, id: PaneId, direction: &Direction) -> Option<PaneId> {
+        let pane = self.panes.get(&id)?;
+        self.get_selectable_panes()
+            .filter(|(_, c)| match direction {
+                Direction::Left => {
+                    c.is_directly_left_of(pane.as_ref())
+                        && c.horizontally_overlaps_with(pane.as_ref())
+                }
+                Direction::Right => {
+                    c.is_directly_right_of(pane.as_ref())
+                        && c.horizontally_overlaps_with(pane.as_ref())
+                }
+                Direction::Up => {
+                    c.is_directly_above(pane.as_ref()) && c.vertically_overlaps_with(pane.as_ref())
+                }
+                Direction::Down => {
+                    c.is_directly_below(pane.as_ref()) && c.vertically_overlaps_with(pane.as_ref())
+                }
+            })
+            .max_by_key(|(_, c)| c.active_at())
+            .map(|(&pid, _)| pid)
+    }
+    // panes with a fixed size (eg. the tab-bar or the status-bar) keep their place when
+    // rearranging, as they would not fit in the space of another pane
+    fn rearrangeable_pane_ids(&self) -> Vec<PaneId> {
+        let mut panes: Vec<(&PaneId, &Box<dyn Pane>)> = self
+            .get_selectable_panes()
+            .filter(|(_, p)| p.max_height().is_none() && p.max_width().is_none())
+            .collect();
+        panes.sort_by(|(_a_id, a_pane), (_b_id, b_pane)| {
+            if a_pane.y() == b_pane.y() {
+                a_pane.x().cmp(&b_pane.x())
+            } else {
+                a_pane.y().cmp(&b_pane.y())
+            }
+        });
+        panes.iter().map(|(id, _)| **id).collect()
+    }
+    fn set_pane_position_and_size(&mut self, id: PaneId, position_and_size: PositionAndSize) {
+        // only the geometry is taken, so that fixed sizes in a layout do not pin the pane
+        let position_and_size = PositionAndSize {
+            x: position_and_size.x,
+            y: position_and_size.y,
+            rows: position_and_size.rows,
+            columns: position_and_size.columns,
+            ..Default::default()
+        };
+        if let Some(pane) = self.panes.get_mut(&id) {
+            let size_changed = pane.columns() != position_and_size.columns
+                || pane.rows() != position_and_size.rows;
+            pane.relocate(&position_and_size);
+            // a pane that only moves keeps its pty as it is
+            if let (PaneId::Terminal(pid), true) = (id, size_changed) {
+                self.os_api.set_terminal_size_using_fd(
+                    pid,
+                    position_and_size.columns as u16,
+                    position_and_size.rows as u16,
+                );
+            }
+        }
+    }
+    fn swap_pane_positions(&mut self, first_id: PaneId, second_id: PaneId) {
+        let first_position = self.panes.get(&first_id).unwrap().position_and_size();
+        let second_position = self.panes.get(&second_id).unwrap().position_and_size();
+        self.set_pane_position_and_size(first_id, second_position);
+        self.set_pane_position_and_size(second_id, first_position);
+    }
+    pub fn swap_active_pane(&mut self, direction: Direction) {
+        if self.fullscreen_is_active {
+            return;
+        }
+        if let Some(active_pane_id) = self.get_active_pane_id() {
+            let rearrangeable_pane_ids = self.rearrangeable_pane_ids();
+            if !rearrangeable_pane_ids.contains(&active_pane_id) {
+                return;
+            }
+            if let Some(other_pane_id) = self.pane_id_in_direction(active_pane_id, &direction) {
+                if rearrangeable_pane_ids.contains(&other_pane_id) {
+                    self.swap_pane_positions(active_pane_id, other_pane_id);
+                    self.render();
+                }
+            }
+        }
+    }
+    pub fn move_active_pane(&mut self, direction: Direction) {
+        if self.fullscreen_is_active {
+            return;
+        }
+        let active_pane_id = match self.get_active_pane_id() {
+            Some(active_pane_id) => active_pane_id,
+            None => return,
+        };
+        let rearrangeable_pane_ids = self.rearrangeable_pane_ids();
+        if !rearrangeable_pane_ids.contains(&active_pane_id) {
+            return;
+        }
+        let target_pane_id = match self.pane_id_in_direction(active_pane_id, &direction) {
+            Some(target_pane_id) if rearrangeable_pane_ids.contains(&target_pane_id) => {
+                target_pane_id
+            }
+            _ => return,
+        };
+        // the target only grows when the active pane leaves its place, so if it can be split
+        // now it can be split then
+        let target_pane = self.panes.get(&target_pane_id).unwrap();
+        let target_can_be_split = match direction {
+            Direction::Left | Direction::Right => {
+                target_pane.columns() > target_pane.min_width() * 2
+            }
+            Direction::Up | Direction::Down => target_pane.rows() > target_pane.min_height() * 2,
+        };
+        if !target_can_be_split || !self.pane_space_can_be_taken(active_pane_id) {
+            return;
+        }
+        let active_pane = match self.close_pane_without_rerender(active_pane_id) {
+            Some(active_pane) => active_pane,
+            None => return,
+        };
+        let target_ws = self.panes.get(&target_pane_id).unwrap().position_and_size();
+        let (first_ws, second_ws) = match direction {
+            Direction::Left | Direction::Right => split_vertically_with_gap(&target_ws),
+            Direction::Up | Direction::Down => split_horizontally_with_gap(&target_ws),
+        };
+        // the active pane takes the side of the target it moved towards
+        let (active_ws, target_ws) = match direction {
+            Direction::Left | Direction::Up => (first_ws, second_ws),
+            Direction::Right | Direction::Down => (second_ws, first_ws),
+        };
+        self.set_pane_position_and_size(target_pane_id, target_ws);
+        self.panes.insert(active_pane_id, active_pane);
+        self.set_pane_position_and_size(active_pane_id, active_ws);
+        self.active_terminal = Some(active_pane_id);
+        self.should_clear_display_before_rendering = true;
+        self.render();
+    }
+    pub fn rotate_panes(&mut self, backwards: bool) {
+        if self.fullscreen_is_active {
+            return;
+        }
+        let pane_ids = self.rearrangeable_pane_ids();
+        if pane_ids.len() < 2 {
+            return;
+        }
+        let mut positions: Vec<PositionAndSize> = pane_ids
+            .iter()
+            .map(|id| self.panes.get(id).unwrap().position_and_size())
+            .collect();
+        // every pane takes the place of the one after it (or before it, when rotating backwards)
+        if backwards {
+            positions.rotate_right(1);
+        } else {
+            positions.rotate_left(1);
+        }
+        for (id, position_and_size) in pane_ids.into_iter().zip(positions.into_iter()) {
+            self.set_pane_position_and_size(id, position_and_size);
+        }
+        self.render();
+    }
+    // the smallest rectangle containing all of the given panes, provided no other pane overlaps it
+    fn space_taken_by_panes(&self, pane_ids: &[PaneId]) -> Option<PositionAndSize> {
+        let panes: Vec<&Box<dyn Pane>> = pane_ids
+            .iter()
+            .filter_map(|id| self.panes.get(id))
+            .collect();
+        let x = panes.iter().map(|p| p.x()).min()?;
+        let y = panes.iter().map(|p| p.y()).min()?;
+        let right = panes.iter().map(|p| p.x() + p.columns()).max()?;
+        let bottom = panes.iter().map(|p| p.y() + p.rows()).max()?;
+        let other_pane_overlaps = self
+            .get_panes()
+            .filter(|(id, _)| !pane_ids.contains(id))
+            .any(|(_, p)| {
+                p.x() < right && p.x() + p.columns() > x && p.y() < bottom && p.y() + p.rows() > y
+            });
+        if other_pane_overlaps {
+            None
+        } else {
+            Some(PositionAndSize {
+                x,
+                y,
+                columns: right - x,
+                rows: bottom - y,
+                ..Default::default()
+            })
+        }
+    }
+    pub fn select_layout_preset(&mut self, preset: LayoutPreset) {
+        if self.fullscreen_is_active {
+            self.toggle_active_pane_fullscreen();
+        }
+        let pane_ids = self.rearrangeable_pane_ids();
+        let space = match self.space_taken_by_panes(&pane_ids) {
+            Some(space) => space,
+            None => return,
+        };
+        let (columns, rows) = preset.grid_size(pane_ids.len());
+        if space.columns < columns * MIN_TERMINAL_WIDTH + columns - 1
+            || space.rows < rows * MIN_TERMINAL_HEIGHT + rows - 1
+        {
+            // there isn't enough room to fit all panes in this preset
+            return;
+        }
+        let positions_in_layout =
+            match Layout::from_preset(preset, pane_ids.len()).position_panes_in_space(&space) {
+                Ok(positions_in_layout) => positions_in_layout,
+                Err(_) => return,
+            };
+        for (id, (_, position_and_size)) in pane_ids.into_iter().zip(positions_in_layout) {
+            self.set_pane_position_and_size(id, position_and_size);
+        }
+        self.layout_preset = Some(preset);
+        self.should_clear_display_before_rendering = true;
+        self.render();
+    }
+    pub fn next_layout_preset(&mut self) {
+        let preset = self
+            .layout_preset
+            .map(LayoutPreset::next)
+            .unwrap_or(LayoutPreset::EvenHorizontal);
+        self.select_layout_preset(preset);
+    }
+    // the splits the rearrangeable panes are currently arranged in, along with the panes in the
+    // order the layout positions them and the space they take
+    fn rearrangeable_panes_as_layout(&self) -> Option<(Layout, Vec<PaneId>, PositionAndSize)> {
+        let pane_ids = self.rearrangeable_pane_ids();
+        let space = self.space_taken_by_panes(&pane_ids)?;
+        let positions: Vec<PositionAndSize> = pane_ids
+            .iter()
+            .map(|id| self.panes.get(id).unwrap().position_and_size())
+            .collect();
+        let (layout, pane_order) = Layout::from_geometry(&space, &positions)?;
+        let pane_ids = pane_order.into_iter().map(|i| pane_ids[i]).collect();
+        Some((layout, pane_ids, space))
+    }
+    fn reposition_panes_in_layout(
+        &mut self,
+        layout: &Layout,
+        pane_ids: Vec<PaneId>,
+        space: &PositionAndSize,
+    ) {
+        let positions_in_layout = match layout.position_panes_in_space(space) {
+            Ok(positions_in_layout) => positions_in_layout,
+            Err(_) => return,
+        };
+        for (id, (_, position_and_size)) in pane_ids.into_iter().zip(positions_in_layout) {
+            self.set_pane_position_and_size(id, position_and_size);
+        }
+        self.should_clear_display_before_rendering = true;
+        self.render();
+    }
+    pub fn equalize_panes(&mut self) {
+        if self.fullscreen_is_active {
+            self.toggle_active_pane_fullscreen();
+        }
+        if let Some((mut layout, pane_ids, space)) = self.rearrangeable_panes_as_layout() {
+            layout.equalize();
+            self.reposition_panes_in_layout(&layout, pane_ids, &space);
+        }
+    }
+    pub fn set_active_pane_width(&mut self, width: PaneSize) {
+        // panes are as wide as the columns of a vertical split
+        self.resize_active_pane_in_layout(LayoutDirection::Vertical, width, MIN_TERMINAL_WIDTH);
+    }
+    pub fn set_active_pane_height(&mut self, height: PaneSize) {
+        // panes are as high as the rows of a horizontal split
+        self.resize_active_pane_in_layout(LayoutDirection::Horizontal, height, MIN_TERMINAL_HEIGHT);
+    }
+    fn resize_active_pane_in_layout(
+        &mut self,
+        direction: LayoutDirection,
+        size: PaneSize,
+        min_pane_size: usize,
+    ) {
+        if self.fullscreen_is_active {
+            self.toggle_active_pane_fullscreen();
+        }
+        let active_pane_id = match self.get_active_pane_id() {
+            Some(active_pane_id) => active_pane_id,
+            None => return,
+        };
+        let split_size = match SplitSize::try_from(size) {
+            Ok(split_size) => split_size,
+            // a size that does not fit in a split does not fit on the screen either
+            Err(_) => return,
+        };
+        if let Some((mut layout, pane_ids, space)) = self.rearrangeable_panes_as_layout() {
+            let pane_index = match pane_ids.iter().position(|id| id == &active_pane_id) {
+                Some(pane_index) => pane_index,
+                None => return,
+            };
+            if layout.resize_pane(pane_index, direction, split_size, min_pane_size) {
+                self.reposition_panes_in_layout(&layout, pane_ids, &space);
+            }
+        }
+    }
+    /// The working directory of the focused pane, as reported by its shell or else that of the
+    /// command running in it. A directory that was deleted since is left out, so that new panes
+    /// start in the default one instead.
+    pub fn active_pane_cwd(&self) -> Option<PathBuf> {
+        let active_terminal_id = self.get_active_terminal_id()?;
+        self.panes
+            .get(&PaneId::Terminal(active_terminal_id))
+            .and_then(|pane| pane.cwd())
+            .filter(|cwd| cwd.is_dir())
+            .or_else(|| {
+                self.os_api
+                    .get_foreground_command(active_terminal_id)
+                    .and_then(|command| command.cwd)
+                    .filter(|cwd| cwd.is_dir())
+            })
+    }
+    /// Describes the panes of this tab, along with what runs in them, as a layout that
+    /// recreates the tab when loaded.
+    pub fn dump_layout(&self) -> Option<TabLayout> {
+        let pane_ids: Vec<PaneId> = self.panes.keys().copied().collect();
+        // a fullscreen pane is saved where it is in the tab
+        let positions: Vec<PositionAndSize> = pane_ids
+            .iter()
+            .map(|id| self.panes.get(id).unwrap().base_position_and_size())
+            .collect();
+        Layout::from_geometry(&self.full_screen_ws, &positions).map(|(mut layout, pane_order)| {
+            let pane_ids: Vec<PaneId> = pane_order.into_iter().map(|i| pane_ids[i]).collect();
+            let fixed_panes: Vec<bool> = pane_ids
+                .iter()
+                .map(|id| {
+                    let pane = self.panes.get(id).unwrap();
+                    pane.max_height().is_some() || pane.max_width().is_some()
+                })
+                .collect();
+            layout.fixed_sizes_to_percentages(&fixed_panes);
+            for (id, pane_layout) in pane_ids.iter().zip(layout.panes_mut()) {
+                match id {
+                    PaneId::Plugin(_) => {
+                        pane_layout.plugin = self.panes.get(id).unwrap().plugin_path();
+                    }
+                    PaneId::Terminal(pid) => {
+                        if let Some(run) = self.os_api.get_foreground_command(*pid) {
+                            pane_layout.run = without_default_shell(run);
+                        }
+                    }
+                }
+                if Some(*id) == self.active_terminal {
+                    pane_layout.focus = Some(true);
+                }
+            }
+            TabLayout {
+                name: self.name.clone(),
+                ..TabLayout::from(layout)
+            }
+        })
+    }
     fn horizontal_borders(&self, terminals: &[PaneId]) -> HashSet<usize> {
         terminals.iter().fold(HashSet::new(), |mut borders, t| {
             let terminal = self.panes.get(t).unwrap();
@@ -2131,7 +2748,32 @@ impl Tab {
             self.close_pane_without_rerender(id);
         }
     }
-    pub fn close_pane_without_rerender(&mut self, id: PaneId) {
+    /// Returns whether the panes next to the pane can take its space if it is closed, rather
+    /// than leaving a hole in the layout (eg. when it is surrounded by fixed size panes).
+    fn pane_space_can_be_taken(&self, id: PaneId) -> bool {
+        let (width, height) = match self.panes.get(&id) {
+            // 1 for the border
+            Some(pane) => (pane.columns() + 1, pane.rows() + 1),
+            None => return false,
+        };
+        let can_grow = |panes: Option<Vec<PaneId>>, horizontally: bool| {
+            panes.map_or(false, |panes| {
+                panes.iter().all(|p| {
+                    let pane = self.panes.get(p).unwrap();
+                    if horizontally {
+                        pane.can_increase_width_by(width)
+                    } else {
+                        pane.can_increase_height_by(height)
+                    }
+                })
+            })
+        };
+        can_grow(self.panes_to_the_left_between_aligning_borders(id), true)
+            || can_grow(self.panes_to_the_right_between_aligning_borders(id), true)
+            || can_grow(self.panes_above_between_aligning_borders(id), false)
+            || can_grow(self.panes_below_between_aligning_borders(id), false)
+    }
+    pub fn close_pane_without_rerender(&mut self, id: PaneId) -> Option<Box<dyn Pane>> {
         if self.fullscreen_is_active {
             self.toggle_active_pane_fullscreen();
         }
@@ -2147,11 +2789,11 @@ impl Tab {
                         self.increase_pane_width_right(&pane_id, pane_to_close_width + 1);
                         // 1 for the border
                     }
-                    self.panes.remove(&id);
+                    let closed_pane = self.panes.remove(&id);
                     if self.active_terminal == Some(id) {
                         self.active_terminal = self.next_active_pane(panes);
                     }
-                    return;
+                    return closed_pane;
                 }
             }
             if let Some(panes) = self.panes_to_the_right_between_aligning_borders(id) {
@@ -2163,11 +2805,11 @@ impl Tab {
                         self.increase_pane_width_left(&pane_id, pane_to_close_width + 1);
                         // 1 for the border
                     }
-                    self.panes.remove(&id);
+                    let closed_pane = self.panes.remove(&id);
                     if self.active_terminal == Some(id) {
                         self.active_terminal = self.next_active_pane(panes);
                     }
-                    return;
+                    return closed_pane;
                 }
             }
             if let Some(panes) = self.panes_above_between_aligning_borders(id) {
@@ -2179,11 +2821,11 @@ impl Tab {
                         self.increase_pane_height_down(&pane_id, pane_to_close_height + 1);
                         // 1 for the border
                     }
-                    self.panes.remove(&id);
+                    let closed_pane = self.panes.remove(&id);
                     if self.active_terminal == Some(id) {
                         self.active_terminal = self.next_active_pane(panes);
                     }
-                    return;
+                    return closed_pane;
                 }
             }
             if let Some(panes) = self.panes_below_between_aligning_borders(id) {
@@ -2195,17 +2837,18 @@ impl Tab {
                         self.increase_pane_height_up(&pane_id, pane_to_close_height + 1);
                         // 1 for the border
                     }
-                    self.panes.remove(&id);
+                    let closed_pane = self.panes.remove(&id);
                     if self.active_terminal == Some(id) {
                         self.active_terminal = self.next_active_pane(panes);
                     }
-                    return;
+                    return closed_pane;
                 }
             }
             // if we reached here, this is either the last pane or there's some sort of
             // configuration error (eg. we're trying to close a pane surrounded by fixed panes)
-            self.panes.remove(&id);
+            return self.panes.remove(&id);
         }
+        None
     }
     pub fn close_focused_pane(&mut self) {
         if let Some(active_pane_id) = self.get_active_pane_id() {
@@ -2269,3 +2912,7 @@ impl Tab {
         }
     }
 }
+
+#[cfg(test)]
+#[path = "./unit/tab_tests.rs"]
+mod tab_tests;
@@ -479,6 +479,9 @@ impl Boundaries {
             },
             false => None,
         };
+        self.add_rect_with_color(rect, color);
+    }
+    pub fn add_rect_with_color(&mut self, rect: &dyn Pane, color: Option<PaletteColor>) {
         if rect.x() > 0 {
             let boundary_x_coords = rect.x() - 1;
             let first_row_coordinates = self.rect_right_boundary_row_start(rect);
@@ -1,3 +1,2 @@
 pub mod boundaries;
-pub mod layout;
 pub mod pane_resizer;
@@ -0,0 +1,86 @@
+use super::*;
+
+fn size(columns: usize, rows: usize) -> PositionAndSize {
+    PositionAndSize {
+        columns,
+        rows,
+        ..Default::default()
+    }
+}
+
+fn smallest_columns_and_rows(clients: &AttachedClients) -> Option<(usize, usize)> {
+    clients
+        .smallest_size()
+        .map(|size| (size.columns, size.rows))
+}
+
+#[test]
+fn no_size_without_clients() {
+    let clients = AttachedClients::default();
+    assert!(clients.is_empty());
+    assert_eq!(smallest_columns_and_rows(&clients), None);
+}
+
+#[test]
+fn several_clients_can_attach() {
+    let mut clients = AttachedClients::default();
+    clients.attach(0, size(121, 20), ClientType::Writer);
+    clients.attach(1, size(80, 24), ClientType::Writer);
+    clients.attach(2, size(100, 10), ClientType::Writer);
+    assert_eq!(clients.len(), 3);
+    assert_eq!(clients.ids().collect::<Vec<_>>(), vec![0, 1, 2]);
+}
+
+#[test]
+fn the_screen_fits_the_smallest_client() {
+    let mut clients = AttachedClients::default();
+    clients.attach(0, size(121, 20), ClientType::Writer);
+    clients.attach(1, size(80, 24), ClientType::Writer);
+    clients.attach(2, size(100, 10), ClientType::Writer);
+    // the narrowest and the shortest terminals are not the same one
+    assert_eq!(smallest_columns_and_rows(&clients), Some((80, 10)));
+}
+
+#[test]
+fn the_screen_grows_when_the_smallest_client_detaches() {
+    let mut clients = AttachedClients::default();
+    clients.attach(0, size(121, 20), ClientType::Writer);
+    clients.attach(1, size(80, 10), ClientType::Writer);
+    assert!(clients.detach(1));
+    assert!(!clients.contains(1));
+    assert_eq!(smallest_columns_and_rows(&clients), Some((121, 20)));
+    assert!(clients.detach(0));
+    assert!(clients.is_empty());
+}
+
+#[test]
+fn detaching_a_client_that_is_not_attached_changes_nothing() {
+    let mut clients = AttachedClients::default();
+    clients.attach(0, size(121, 20), ClientType::Writer);
+    assert!(!clients.detach(1));
+    assert_eq!(clients.len(), 1);
+}
+
+#[test]
+fn the_screen_follows_a_client_that_resizes() {
+    let mut clients = AttachedClients::default();
+    clients.attach(0, size(121, 20), ClientType::Writer);
+    clients.attach(1, size(100, 24), ClientType::Writer);
+    assert!(clients.resize(1, size(60, 30)));
+    assert_eq!(smallest_columns_and_rows(&clients), Some((60, 20)));
+    // clients that only query the session are not attached, and do not size it
+    assert!(!clients.resize(2, size(10, 10)));
+    assert_eq!(smallest_columns_and_rows(&clients), Some((60, 20)));
+}
+
+#[test]
+fn read_only_clients_do_not_size_the_screen() {
+    let mut clients = AttachedClients::default();
+    clients.attach(0, size(121, 20), ClientType::Writer);
+    clients.attach(1, size(60, 10), ClientType::Reader);
+    assert_eq!(clients.len(), 2);
+    assert_eq!(smallest_columns_and_rows(&clients), Some((121, 20)));
+    // the screen keeps its size while only read-only clients watch it
+    assert!(clients.detach(0));
+    assert_eq!(smallest_columns_and_rows(&clients), None);
+}
@@ -0,0 +1,80 @@
+use super::*;
+
+#[test]
+fn spawn_error_names_the_program() {
+    let program = Path::new("/this/program/does/not/exist");
+    let error = Command::new(program).spawn().unwrap_err();
+    let message = spawn_error_message(program, None, &error);
+    assert!(
+        message.starts_with("Could not start /this/program/does/not/exist: "),
+        "unexpected message: {}",
+        message
+    );
+}
+
+#[test]
+fn spawn_error_names_the_working_directory() {
+    let program = Path::new("/bin/sh");
+    let cwd = Path::new("/this/directory/does/not/exist");
+    let error = Command::new(program).current_dir(cwd).spawn().unwrap_err();
+    let message = spawn_error_message(program, Some(cwd), &error);
+    assert!(
+        message.starts_with("Could not start /bin/sh in /this/directory/does/not/exist: "),
+        "unexpected message: {}",
+        message
+    );
+}
+
+#[test]
+fn exit_status_message_shows_the_exit_code() {
+    // a raw wait status holds the exit code in its second byte
+    let exit_status = ExitStatus::from_raw(2 << 8);
+    assert_eq!(
+        exit_status_message(Some(exit_status)),
+        "Command exited with code 2"
+    );
+}
+
+#[test]
+fn exit_status_message_shows_the_signal() {
+    let exit_status = ExitStatus::from_raw(9);
+    assert_eq!(
+        exit_status_message(Some(exit_status)),
+        "Command was killed by signal 9"
+    );
+}
+
+#[test]
+fn exit_status_message_without_status() {
+    assert_eq!(exit_status_message(None), "Command exited");
+}
+
+#[test]
+fn exit_code_is_that_of_the_command() {
+    assert_eq!(exit_code(Some(ExitStatus::from_raw(0))), 0);
+    assert_eq!(exit_code(Some(ExitStatus::from_raw(2 << 8))), 2);
+}
+
+#[test]
+fn exit_code_of_a_killed_command_tells_the_signal() {
+    assert_eq!(exit_code(Some(ExitStatus::from_raw(9))), 137);
+}
+
+#[test]
+fn exit_code_without_status_is_a_failure() {
+    assert_eq!(exit_code(None), 1);
+}
+
+#[test]
+fn terminal_ids_are_not_reused() {
+    let mut terminal_ids = TerminalIds::default();
+    let first_id = terminal_ids.next_id();
+    terminal_ids.insert(first_id, 10);
+    let second_id = terminal_ids.next_id();
+    assert_ne!(first_id, second_id);
+    // the terminal of the first pane was closed, and its file descriptor given to this one
+    terminal_ids.insert(second_id, 10);
+    assert_eq!(terminal_ids.fd(first_id), None);
+    assert_eq!(terminal_ids.fd(second_id), Some(10));
+    assert_eq!(terminal_ids.id(10), Some(second_id));
+}
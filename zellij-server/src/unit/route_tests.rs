@@ -0,0 +1,363 @@
+use super::*;
+
+use std::path::PathBuf;
+use std::sync::{mpsc, Mutex};
+use std::thread;
+
+use crate::os_input_output::{AsyncReader, Pid};
+use crate::thread_bus::ThreadSenders;
+use zellij_utils::{
+    channels::SenderType,
+    errors::ErrorContext,
+    input::command::{RunCommand, TerminalAction},
+    interprocess::local_socket::LocalSocketStream,
+    ipc::ClientAttributes,
+    nix,
+    zellij_tile::data::{Palette, PluginCapabilities},
+};
+
+/// A client connected to the router, that sends the given messages and then goes away.
+#[derive(Clone)]
+struct FakeClient {
+    from_client: Arc<Mutex<mpsc::Receiver<ClientToServerMsg>>>,
+    to_client: Arc<Mutex<Vec<ServerToClientMsg>>>,
+}
+
+impl ServerOsApi for FakeClient {
+    fn set_terminal_size_using_fd(&self, _fd: RawFd, _cols: u16, _rows: u16) {
+        unimplemented!()
+    }
+    fn spawn_terminal(&self, _terminal_action: Option<TerminalAction>) -> (RawFd, Pid) {
+        unimplemented!()
+    }
+    fn read_from_tty_stdout(&self, _fd: RawFd, _buf: &mut [u8]) -> Result<usize, nix::Error> {
+        unimplemented!()
+    }
+    fn async_file_reader(&self, _fd: RawFd) -> Box<dyn AsyncReader> {
+        unimplemented!()
+    }
+    fn write_to_tty_stdin(&self, _fd: RawFd, _buf: &[u8]) -> Result<usize, nix::Error> {
+        unimplemented!()
+    }
+    fn tcdrain(&self, _fd: RawFd) -> Result<(), nix::Error> {
+        unimplemented!()
+    }
+    fn kill(&self, _pid: Pid) -> Result<(), nix::Error> {
+        unimplemented!()
+    }
+    fn box_clone(&self) -> Box<dyn ServerOsApi> {
+        Box::new(self.clone())
+    }
+    fn recv_from_client(&self) -> Option<(ClientToServerMsg, ErrorContext)> {
+        let msg = self.from_client.lock().unwrap().recv().ok()?;
+        Some((msg, ErrorContext::new()))
+    }
+    fn send_to_client(&self, _client_id: ClientId, msg: ServerToClientMsg) {
+        self.to_client.lock().unwrap().push(msg);
+    }
+    fn add_client_sender(&self, _client_id: ClientId) {}
+    fn send_to_temp_client(&self, msg: ServerToClientMsg) {
+        self.to_client.lock().unwrap().push(msg);
+    }
+    fn remove_client_sender(&self, _client_id: ClientId) {}
+    fn update_receiver(&mut self, _stream: LocalSocketStream) {
+        unimplemented!()
+    }
+    fn load_palette(&self) -> Palette {
+        Palette::default()
+    }
+    fn get_foreground_command(&self, _fd: RawFd) -> Option<RunCommand> {
+        None
+    }
+    fn terminal_id(&self, fd: RawFd) -> Option<u32> {
+        Some(fd as u32)
+    }
+    fn terminal_fd(&self, terminal_id: u32) -> Option<RawFd> {
+        // only the pane with id 1 is open
+        if terminal_id == 1 {
+            Some(10)
+        } else {
+            None
+        }
+    }
+}
+
+/// What the router sent on, to the client and to the other threads of the session.
+struct Routed {
+    to_client: Vec<ServerToClientMsg>,
+    to_server: Vec<ServerInstruction>,
+    to_screen: Vec<ScreenInstruction>,
+}
+
+/// Routes the messages of a client to an attached session, until the client goes away.
+fn route(messages: Vec<ClientToServerMsg>) -> Routed {
+    let (to_screen, screen_receiver) = mpsc::channel();
+    let (to_pty, _pty_receiver) = mpsc::channel();
+    let (to_plugin, _plugin_receiver) = mpsc::channel();
+    let (to_server, server_receiver) = mpsc::channel();
+    let session = SessionMetaData {
+        senders: ThreadSenders {
+            to_screen: Some(SenderWithContext::new(SenderType::Sender(to_screen))),
+            to_pty: Some(SenderWithContext::new(SenderType::Sender(to_pty))),
+            to_plugin: Some(SenderWithContext::new(SenderType::Sender(to_plugin))),
+            to_server: None,
+        },
+        capabilities: PluginCapabilities::default(),
+        screen_thread: Some(thread::spawn(|| {})),
+        pty_thread: Some(thread::spawn(|| {})),
+        wasm_thread: Some(thread::spawn(|| {})),
+    };
+    let (client_sender, from_client) = mpsc::channel();
+    for msg in messages {
+        client_sender.send(msg).unwrap();
+    }
+    drop(client_sender);
+    let client = FakeClient {
+        from_client: Arc::new(Mutex::new(from_client)),
+        to_client: Arc::new(Mutex::new(Vec::new())),
+    };
+    route_thread_main(
+        Arc::new(RwLock::new(Some(session))),
+        Arc::new(RwLock::new(SessionState::Attached)),
+        client.box_clone(),
+        SenderWithContext::new(SenderType::Sender(to_server)),
+        0,
+    );
+    let to_client = client.to_client.lock().unwrap().drain(..).collect();
+    Routed {
+        to_client,
+        to_server: server_receiver.try_iter().map(|(i, _)| i).collect(),
+        to_screen: screen_receiver.try_iter().map(|(i, _)| i).collect(),
+    }
+}
+
+fn attach(force: bool, client_type: ClientType) -> ClientToServerMsg {
+    let attrs = ClientAttributes {
+        position_and_size: Default::default(),
+        palette: Palette::default(),
+    };
+    ClientToServerMsg::AttachClient(attrs, force, client_type)
+}
+
+fn write_x() -> ClientToServerMsg {
+    ClientToServerMsg::Action(Action::Write(vec![120]))
+}
+
+#[test]
+fn a_writer_can_write_to_the_session() {
+    let routed = route(vec![attach(false, ClientType::Writer), write_x()]);
+    assert!(routed
+        .to_screen
+        .iter()
+        .any(|i| matches!(i, ScreenInstruction::WriteCharacter(_))));
+}
+
+#[test]
+fn a_reader_cannot_write_to_the_session() {
+    let routed = route(vec![attach(false, ClientType::Reader), write_x()]);
+    assert!(!routed
+        .to_screen
+        .iter()
+        .any(|i| matches!(i, ScreenInstruction::WriteCharacter(_))));
+    // its input thread does not wait for the write forever
+    assert!(routed
+        .to_client
+        .iter()
+        .any(|msg| matches!(msg, ServerToClientMsg::UnblockInputThread)));
+}
+
+#[test]
+fn a_reader_can_detach() {
+    let routed = route(vec![
+        attach(false, ClientType::Reader),
+        ClientToServerMsg::Action(Action::Detach),
+    ]);
+    assert!(routed
+        .to_server
+        .iter()
+        .any(|i| matches!(i, ServerInstruction::DetachSession(0))));
+}
+
+#[test]
+fn a_writer_can_force_attach() {
+    let routed = route(vec![attach(true, ClientType::Writer)]);
+    assert!(routed.to_server.iter().any(|i| matches!(
+        i,
+        ServerInstruction::AttachClient(_, true, ClientType::Writer, 0)
+    )));
+}
+
+#[test]
+fn a_reader_cannot_force_attach() {
+    let routed = route(vec![attach(true, ClientType::Reader)]);
+    assert!(routed.to_server.iter().any(|i| matches!(
+        i,
+        ServerInstruction::AttachClient(_, false, ClientType::Reader, 0)
+    )));
+}
+
+#[test]
+fn a_reader_cannot_kill_the_session() {
+    let routed = route(vec![
+        attach(false, ClientType::Reader),
+        ClientToServerMsg::KillSession,
+    ]);
+    assert!(!routed
+        .to_server
+        .iter()
+        .any(|i| matches!(i, ServerInstruction::ClientExit)));
+}
+
+#[test]
+fn a_writer_can_kill_the_session() {
+    let routed = route(vec![
+        attach(false, ClientType::Writer),
+        ClientToServerMsg::KillSession,
+    ]);
+    assert!(routed
+        .to_server
+        .iter()
+        .any(|i| matches!(i, ServerInstruction::ClientExit)));
+}
+
+#[test]
+fn a_reader_cannot_write_to_a_pane() {
+    let routed = route(vec![
+        attach(false, ClientType::Reader),
+        ClientToServerMsg::WriteToPane(1, vec![120]),
+    ]);
+    assert!(!routed
+        .to_screen
+        .iter()
+        .any(|i| matches!(i, ScreenInstruction::WriteToTerminal(..))));
+}
+
+#[test]
+fn the_session_name_is_entered_with_enter() {
+    let mut name = String::new();
+    assert_eq!(update_session_name_input(&mut name, &[0]), None);
+    assert_eq!(update_session_name_input(&mut name, b"work"), None);
+    assert_eq!(update_session_name_input(&mut name, b"s"), None);
+    assert_eq!(update_session_name_input(&mut name, &[127]), None);
+    assert_eq!(
+        update_session_name_input(&mut name, &[10]),
+        Some("work".to_string())
+    );
+    assert!(name.is_empty());
+}
+
+#[test]
+fn escape_gives_up_on_the_session_name() {
+    let mut name = String::new();
+    update_session_name_input(&mut name, b"work");
+    update_session_name_input(&mut name, &[27]);
+    assert_eq!(
+        update_session_name_input(&mut name, &[10]),
+        Some(String::new())
+    );
+}
+
+#[test]
+fn a_session_name_typed_in_rename_mode_renames_the_session() {
+    let mut messages = vec![attach(false, ClientType::Writer)];
+    for input in [vec![0], b"work".to_vec(), vec![10]].iter() {
+        messages.push(ClientToServerMsg::Action(Action::SessionNameInput(
+            input.clone(),
+        )));
+    }
+    let routed = route(messages);
+    assert!(routed
+        .to_server
+        .iter()
+        .any(|i| matches!(i, ServerInstruction::RenameSession(name, None) if name == "work")));
+}
+
+#[test]
+fn a_reader_cannot_rename_the_session() {
+    let routed = route(vec![
+        attach(false, ClientType::Reader),
+        ClientToServerMsg::Action(Action::SessionNameInput(b"work".to_vec())),
+        ClientToServerMsg::Action(Action::SessionNameInput(vec![10])),
+    ]);
+    assert!(!routed
+        .to_server
+        .iter()
+        .any(|i| matches!(i, ServerInstruction::RenameSession(..))));
+}
+
+#[test]
+fn renaming_from_the_command_line_is_answered() {
+    let routed = route(vec![ClientToServerMsg::RenameSession("work".to_string())]);
+    assert!(routed
+        .to_server
+        .iter()
+        .any(|i| matches!(i, ServerInstruction::RenameSession(name, Some(0)) if name == "work")));
+}
+
+#[test]
+fn writing_to_a_pane_goes_to_its_terminal() {
+    let routed = route(vec![ClientToServerMsg::WriteToPane(1, vec![120])]);
+    assert!(routed
+        .to_screen
+        .iter()
+        .any(|i| matches!(i, ScreenInstruction::WriteToTerminal(10, _, 0))));
+}
+
+#[test]
+fn writing_to_an_unknown_pane_fails() {
+    let routed = route(vec![ClientToServerMsg::WriteToPane(2, vec![120])]);
+    assert!(!routed
+        .to_screen
+        .iter()
+        .any(|i| matches!(i, ScreenInstruction::WriteToTerminal(..))));
+    assert!(routed
+        .to_client
+        .iter()
+        .any(|msg| matches!(msg, ServerToClientMsg::ActionResult(Err(_)))));
+}
+
+#[test]
+fn dumping_the_layout_from_the_command_line_is_answered() {
+    let path = PathBuf::from("/tmp/session.yaml");
+    let routed = route(vec![ClientToServerMsg::DumpLayout(path.clone())]);
+    assert!(routed.to_screen.iter().any(
+        |i| matches!(i, ScreenInstruction::DumpLayout(dump_path, Some(0)) if *dump_path == path)
+    ));
+}
+
+#[test]
+fn a_writer_can_dump_the_layout() {
+    let path = PathBuf::from("/tmp/session.yaml");
+    let routed = route(vec![
+        attach(false, ClientType::Writer),
+        ClientToServerMsg::Action(Action::DumpLayout(path.clone())),
+    ]);
+    assert!(routed.to_screen.iter().any(
+        |i| matches!(i, ScreenInstruction::DumpLayout(dump_path, None) if *dump_path == path)
+    ));
+}
+
+#[test]
+fn a_reader_cannot_rename_the_session_from_the_command_line() {
+    let routed = route(vec![
+        attach(false, ClientType::Reader),
+        ClientToServerMsg::RenameSession("work".to_string()),
+    ]);
+    assert!(!routed
+        .to_server
+        .iter()
+        .any(|i| matches!(i, ServerInstruction::RenameSession(..))));
+}
+
+#[test]
+fn a_reader_cannot_dump_the_layout() {
+    let routed = route(vec![
+        attach(false, ClientType::Reader),
+        ClientToServerMsg::DumpLayout(PathBuf::from("/tmp/session.yaml")),
+        ClientToServerMsg::Action(Action::DumpLayout(PathBuf::from("/tmp/session.yaml"))),
+    ]);
+    assert!(!routed
+        .to_screen
+        .iter()
+        .any(|i| matches!(i, ScreenInstruction::DumpLayout(..))));
+}
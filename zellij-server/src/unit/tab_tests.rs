@@ -0,0 +1,137 @@
+use super::*;
+
+use std::sync::mpsc;
+
+use crate::os_input_output::{AsyncReader, Pid};
+use zellij_utils::{
+    channels::{SenderType, SenderWithContext},
+    errors::ErrorContext,
+    input::command::TerminalAction,
+    interprocess::local_socket::LocalSocketStream,
+    ipc::{ClientId, ClientToServerMsg, ServerToClientMsg},
+    nix,
+};
+
+/// An os api for a tab whose terminals are only ever resized and described.
+#[derive(Clone)]
+struct FakeTabOsApi;
+
+impl ServerOsApi for FakeTabOsApi {
+    fn set_terminal_size_using_fd(&self, _fd: RawFd, _cols: u16, _rows: u16) {}
+    fn spawn_terminal(&self, _terminal_action: Option<TerminalAction>) -> (RawFd, Pid) {
+        unimplemented!()
+    }
+    fn read_from_tty_stdout(&self, _fd: RawFd, _buf: &mut [u8]) -> Result<usize, nix::Error> {
+        unimplemented!()
+    }
+    fn async_file_reader(&self, _fd: RawFd) -> Box<dyn AsyncReader> {
+        unimplemented!()
+    }
+    fn write_to_tty_stdin(&self, _fd: RawFd, _buf: &[u8]) -> Result<usize, nix::Error> {
+        unimplemented!()
+    }
+    fn tcdrain(&self, _fd: RawFd) -> Result<(), nix::Error> {
+        unimplemented!()
+    }
+    fn kill(&self, _pid: Pid) -> Result<(), nix::Error> {
+        unimplemented!()
+    }
+    fn box_clone(&self) -> Box<dyn ServerOsApi> {
+        Box::new(self.clone())
+    }
+    fn recv_from_client(&self) -> Option<(ClientToServerMsg, ErrorContext)> {
+        unimplemented!()
+    }
+    fn send_to_client(&self, _client_id: ClientId, _msg: ServerToClientMsg) {
+        unimplemented!()
+    }
+    fn add_client_sender(&self, _client_id: ClientId) {
+        unimplemented!()
+    }
+    fn send_to_temp_client(&self, _msg: ServerToClientMsg) {
+        unimplemented!()
+    }
+    fn remove_client_sender(&self, _client_id: ClientId) {
+        unimplemented!()
+    }
+    fn update_receiver(&mut self, _stream: LocalSocketStream) {
+        unimplemented!()
+    }
+    fn load_palette(&self) -> Palette {
+        Palette::default()
+    }
+    fn get_foreground_command(&self, _fd: RawFd) -> Option<RunCommand> {
+        None
+    }
+    fn terminal_id(&self, fd: RawFd) -> Option<u32> {
+        Some(fd as u32)
+    }
+    fn terminal_fd(&self, terminal_id: u32) -> Option<RawFd> {
+        Some(terminal_id as RawFd)
+    }
+}
+
+fn tab_with_terminal(fd: RawFd) -> Tab {
+    let (to_plugin, _plugin_receiver) = mpsc::channel();
+    let senders = ThreadSenders {
+        to_screen: None,
+        to_pty: None,
+        to_plugin: Some(SenderWithContext::new(SenderType::Sender(to_plugin))),
+        to_server: None,
+    };
+    let full_screen_ws = PositionAndSize {
+        columns: 121,
+        rows: 20,
+        ..Default::default()
+    };
+    Tab::new(
+        0,
+        0,
+        String::from("tab"),
+        &full_screen_ws,
+        Box::new(FakeTabOsApi),
+        senders,
+        None,
+        Some(PaneId::Terminal(fd)),
+        ModeInfo::default(),
+        InputMode::Normal,
+        Palette::default(),
+        Arc::new(RwLock::new(SessionState::Attached)),
+    )
+}
+
+#[test]
+fn terminal_and_plugin_panes_with_the_same_id_are_told_apart() {
+    let mut tab = tab_with_terminal(1);
+    let (to_plugin, _plugin_receiver) = mpsc::channel();
+    let plugin_pane = PluginPane::new(
+        1,
+        PositionAndSize {
+            y: 20,
+            columns: 121,
+            rows: 1,
+            ..Default::default()
+        },
+        SenderWithContext::new(SenderType::Sender(to_plugin)),
+        PathBuf::from("status-bar"),
+    );
+    tab.panes
+        .insert(PaneId::Plugin(1), Box::new(plugin_pane) as Box<dyn Pane>);
+
+    let nodes = tab.pane_nodes();
+    assert_eq!(nodes.len(), 2);
+    let terminal = nodes
+        .iter()
+        .find(|node| node.kind == PaneKind::Terminal)
+        .expect("the terminal pane is described");
+    let plugin = nodes
+        .iter()
+        .find(|node| node.kind == PaneKind::Plugin)
+        .expect("the plugin pane is described");
+    assert_eq!(terminal.id, 1);
+    assert_eq!(plugin.id, 1);
+    assert_eq!(terminal.plugin, None);
+    assert_eq!(plugin.plugin, Some(PathBuf::from("status-bar")));
+    assert!(terminal.focused);
+    assert!(!plugin.focused);
+}
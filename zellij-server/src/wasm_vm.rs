@@ -23,7 +23,10 @@ use crate::{
     screen::ScreenInstruction,
     thread_bus::{Bus, ThreadSenders},
 };
-use zellij_utils::errors::{ContextType, PluginContext};
+use zellij_utils::{
+    errors::{ContextType, PluginContext},
+    input::command::TerminalAction,
+};
 
 #[derive(Clone, Debug)]
 pub(crate) enum PluginInstruction {
@@ -223,7 +226,9 @@ fn host_open_file(plugin_env: &PluginEnv) {
     let path: PathBuf = wasi_read_object(&plugin_env.wasi_env);
     plugin_env
         .senders
-        .send_to_pty(PtyInstruction::SpawnTerminal(Some(path)))
+        .send_to_pty(PtyInstruction::SpawnTerminal(Some(
+            TerminalAction::OpenFile(path),
+        )))
         .unwrap();
 }
 
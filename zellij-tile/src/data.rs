@@ -32,6 +32,7 @@ pub enum Event {
     TabUpdate(Vec<TabInfo>),
     KeyPress(Key),
     Timer(f64),
+    SessionRename(String),
 }
 
 /// Describes the different input modes, which change the way that keystrokes will be interpreted.
@@ -62,6 +63,9 @@ pub enum InputMode {
     /// `Session` mode allows detaching sessions
     #[serde(alias = "session")]
     Session,
+    /// `RenameSession` mode asks for a new name for the session
+    #[serde(alias = "renamesession")]
+    RenameSession,
 }
 
 impl Default for InputMode {
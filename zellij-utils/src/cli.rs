@@ -1,9 +1,12 @@
 use crate::consts::{ZELLIJ_CONFIG_DIR_ENV, ZELLIJ_CONFIG_FILE_ENV};
+use crate::input::actions::{Action, Direction, LayoutPreset, PaneSize};
 use crate::input::options::Options;
 use crate::setup::Setup;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::PathBuf;
 use structopt::StructOpt;
+use zellij_tile::data::InputMode;
 
 #[derive(StructOpt, Default, Debug, Clone, Serialize, Deserialize)]
 #[structopt(name = "zellij")]
@@ -32,6 +35,10 @@ pub struct CliArgs {
     #[structopt(long, parse(from_os_str))]
     pub layout_path: Option<PathBuf>,
 
+    /// Set a variable used in the layout as {{name}}, eg. --var name=value (may be repeated)
+    #[structopt(long = "var", parse(try_from_str = parse_layout_variable), number_of_values = 1)]
+    pub layout_variables: Vec<(String, String)>,
+
     /// Change where zellij looks for the configuration file
     #[structopt(short, long, env=ZELLIJ_CONFIG_FILE_ENV, parse(from_os_str))]
     pub config: Option<PathBuf>,
@@ -47,6 +54,23 @@ pub struct CliArgs {
     pub debug: bool,
 }
 
+fn parse_layout_variable(variable: &str) -> Result<(String, String), String> {
+    match variable.find('=') {
+        Some(separator) => Ok((
+            variable[..separator].to_string(),
+            variable[separator + 1..].to_string(),
+        )),
+        None => Err(format!("expected name=value, got `{}`", variable)),
+    }
+}
+
+impl CliArgs {
+    /// The variables to substitute in the layout, by name.
+    pub fn layout_variables(&self) -> HashMap<String, String> {
+        self.layout_variables.iter().cloned().collect()
+    }
+}
+
 #[derive(Debug, StructOpt, Clone, Serialize, Deserialize)]
 pub enum Command {
     /// Change the behaviour of zellij
@@ -60,13 +84,344 @@ pub enum Command {
     /// Explore existing zellij sessions
     #[structopt(flatten)]
     Sessions(Sessions),
+
+    /// Send actions to a running zellij session
+    #[structopt(name = "action")]
+    Action {
+        /// Name of the session to send the actions to, instead of the current one
+        #[structopt(long, short)]
+        session: Option<String>,
+
+        #[structopt(subcommand)]
+        action: CliAction,
+    },
+
+    /// Run a command in a new pane of the current zellij session
+    #[structopt(name = "run")]
+    Run {
+        /// Direction to open the pane in (left, right, up, down), instead of
+        /// the biggest available space
+        #[structopt(long, short)]
+        direction: Option<Direction>,
+
+        /// Directory to run the command in, instead of the current one
+        #[structopt(long, parse(from_os_str))]
+        cwd: Option<PathBuf>,
+
+        /// Name of the new pane
+        #[structopt(long, short)]
+        name: Option<String>,
+
+        /// The command to run, followed by its arguments
+        #[structopt(required = true)]
+        command: Vec<String>,
+    },
+
+    /// Write to a pane of the current zellij session, whether it is focused or not
+    #[structopt(name = "write-to-pane")]
+    WriteToPane {
+        /// Id of the pane to write to, as in its ZELLIJ_PANE_ID
+        #[structopt(long)]
+        pane: u32,
+
+        /// The characters to write; \n, \r, \t and \\ are unescaped.
+        /// If none are given, standard input is written instead
+        chars: Option<String>,
+    },
+
+    /// Print the tabs and panes of the current zellij session as JSON
+    #[structopt(name = "query-session")]
+    QuerySession {
+        /// Name of the session to query, instead of the current one
+        #[structopt(long, short)]
+        session: Option<String>,
+    },
+
+    /// Print the contents of a pane of the current zellij session
+    #[structopt(name = "capture-pane")]
+    CapturePane {
+        /// Id of the pane to capture, instead of the focused one
+        #[structopt(long)]
+        pane: Option<u32>,
+
+        /// Include the lines scrolled above the screen
+        #[structopt(long)]
+        scrollback: bool,
+
+        /// Keep the colors and styles, as ANSI escape sequences
+        #[structopt(long)]
+        ansi: bool,
+    },
+}
+
+#[derive(Debug, StructOpt, Clone, Serialize, Deserialize)]
+pub enum CliAction {
+    /// Write bytes to the focused pane
+    Write {
+        /// The bytes to write, as numbers (eg. 27 for escape)
+        bytes: Vec<u8>,
+    },
+
+    /// Write characters to the focused pane
+    WriteChars {
+        /// The characters to write; \n, \r, \t and \\ are unescaped
+        chars: String,
+    },
+
+    /// Switch to an input mode (eg. normal, locked, pane)
+    SwitchMode {
+        #[structopt(parse(try_from_str = parse_input_mode))]
+        mode: InputMode,
+    },
+
+    /// Resize the focused pane in a direction (left, right, up, down)
+    Resize { direction: Direction },
+
+    /// Focus the next pane
+    FocusNextPane,
+
+    /// Focus the previous pane
+    FocusPreviousPane,
+
+    /// Focus the next pane, in the order they were opened
+    SwitchFocus,
+
+    /// Move the focus to the pane in a direction (left, right, up, down)
+    MoveFocus { direction: Direction },
+
+    /// Move the focus to the pane in a direction, or to the next tab
+    /// if there is no pane there
+    MoveFocusOrTab { direction: Direction },
+
+    /// Scroll up in the focused pane
+    ScrollUp,
+
+    /// Scroll down in the focused pane
+    ScrollDown,
+
+    /// Scroll up one page in the focused pane
+    PageScrollUp,
+
+    /// Scroll down one page in the focused pane
+    PageScrollDown,
+
+    /// Toggle the focused pane between fullscreen and its place in the layout
+    ToggleFullscreen,
+
+    /// Swap the focused pane with the pane in a direction (left, right, up, down)
+    SwapPane { direction: Direction },
+
+    /// Move the focused pane into a split of the pane in a direction (left, right, up, down)
+    MovePane { direction: Direction },
+
+    /// Rotate the positions of the panes in the current tab
+    RotatePanes {
+        /// Rotate backwards instead
+        #[structopt(long)]
+        backwards: bool,
+    },
+
+    /// Move the focused pane into a new tab
+    BreakPane,
+
+    /// Move the focused pane into the tab at a position (starting from 1)
+    JoinPaneToTab { tab_position: u32 },
+
+    /// Re-tile the current tab into the next preset layout, or the given one
+    /// (even-horizontal, even-vertical, main-vertical, main-horizontal, tiled)
+    SelectLayout { layout: Option<LayoutPreset> },
+
+    /// Give all panes of the current tab an equal share of the space
+    EqualizePanes,
+
+    /// Resize the focused pane to an exact size
+    ResizePane {
+        /// Width in columns (eg. 40) or as a percentage (eg. 70%)
+        #[structopt(long)]
+        width: Option<PaneSize>,
+
+        /// Height in rows (eg. 20) or as a percentage (eg. 30%)
+        #[structopt(long)]
+        height: Option<PaneSize>,
+    },
+
+    /// Toggle sending input to all panes of the current tab
+    ToggleSyncTab,
+
+    /// Make the focused pane a member of a sync group, whose panes all receive the same input
+    JoinSyncGroup {
+        /// Name of the sync group
+        name: String,
+    },
+
+    /// Remove the focused pane from its sync group
+    LeaveSyncGroup,
+
+    /// Open a new pane
+    NewPane {
+        /// Direction to open the pane in (left, right, up, down), instead of
+        /// the biggest available space
+        #[structopt(long, short)]
+        direction: Option<Direction>,
+    },
+
+    /// Close the focused pane
+    ClosePane,
+
+    /// Open a new tab
+    NewTab,
+
+    /// Go to the next tab
+    GoToNextTab,
+
+    /// Go to the previous tab
+    GoToPreviousTab,
+
+    /// Close the current tab
+    CloseTab,
+
+    /// Go to the tab at a position (starting from 1)
+    GoToTab { tab_position: u32 },
+
+    /// Move the current tab in a direction (left, right)
+    MoveTab { direction: Direction },
+
+    /// Move the current tab to a position (starting from 1)
+    MoveTabToPosition { tab_position: u32 },
+
+    /// Rename the current tab
+    RenameTab { name: String },
+
+    /// Save the tabs and panes of the current session to a layout file
+    DumpLayout {
+        /// Path of the layout file to write
+        #[structopt(parse(from_os_str))]
+        path: PathBuf,
+    },
+
+    /// Rename the current session
+    RenameSession {
+        /// New name of the session
+        name: String,
+    },
+
+    /// Quit the session, closing all of its panes
+    Quit,
+}
+
+fn parse_input_mode(mode: &str) -> Result<InputMode, String> {
+    // the input modes deserialize from their lowercase names, as in the config
+    serde_yaml::from_str(mode).map_err(|_| format!("unknown input mode `{}`", mode))
+}
+
+/// Replaces the escape sequences a shell leaves in a double quoted string.
+pub fn unescape(chars: &str) -> String {
+    let mut unescaped = String::with_capacity(chars.len());
+    let mut chars = chars.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => unescaped.push('\n'),
+            Some('r') => unescaped.push('\r'),
+            Some('t') => unescaped.push('\t'),
+            Some('\\') => unescaped.push('\\'),
+            Some(other) => {
+                unescaped.push('\\');
+                unescaped.push(other);
+            }
+            None => unescaped.push('\\'),
+        }
+    }
+    unescaped
+}
+
+impl CliAction {
+    /// The actions to send to the session for this command.
+    pub fn actions(&self) -> Vec<Action> {
+        match self {
+            CliAction::Write { bytes } => vec![Action::Write(bytes.clone())],
+            CliAction::WriteChars { chars } => vec![Action::Write(unescape(chars).into_bytes())],
+            CliAction::SwitchMode { mode } => vec![Action::SwitchToMode(*mode)],
+            CliAction::Resize { direction } => vec![Action::Resize(direction.clone())],
+            CliAction::FocusNextPane => vec![Action::FocusNextPane],
+            CliAction::FocusPreviousPane => vec![Action::FocusPreviousPane],
+            CliAction::SwitchFocus => vec![Action::SwitchFocus],
+            CliAction::MoveFocus { direction } => vec![Action::MoveFocus(direction.clone())],
+            CliAction::MoveFocusOrTab { direction } => {
+                vec![Action::MoveFocusOrTab(direction.clone())]
+            }
+            CliAction::ScrollUp => vec![Action::ScrollUp],
+            CliAction::ScrollDown => vec![Action::ScrollDown],
+            CliAction::PageScrollUp => vec![Action::PageScrollUp],
+            CliAction::PageScrollDown => vec![Action::PageScrollDown],
+            CliAction::ToggleFullscreen => vec![Action::ToggleFocusFullscreen],
+            CliAction::SwapPane { direction } => vec![Action::SwapPane(direction.clone())],
+            CliAction::MovePane { direction } => vec![Action::MovePane(direction.clone())],
+            CliAction::RotatePanes { backwards: false } => vec![Action::RotatePanes],
+            CliAction::RotatePanes { backwards: true } => vec![Action::RotatePanesBackwards],
+            CliAction::BreakPane => vec![Action::BreakPane],
+            CliAction::JoinPaneToTab { tab_position } => {
+                vec![Action::JoinPaneToTab(*tab_position)]
+            }
+            CliAction::SelectLayout { layout } => match layout {
+                Some(layout) => vec![Action::SelectLayout(*layout)],
+                None => vec![Action::NextLayout],
+            },
+            CliAction::EqualizePanes => vec![Action::EqualizePanes],
+            CliAction::ResizePane { width, height } => {
+                let mut actions = vec![];
+                if let Some(width) = width {
+                    actions.push(Action::SetPaneWidth(*width));
+                }
+                if let Some(height) = height {
+                    actions.push(Action::SetPaneHeight(*height));
+                }
+                actions
+            }
+            CliAction::ToggleSyncTab => vec![Action::ToggleActiveSyncTab],
+            CliAction::JoinSyncGroup { name } => vec![Action::JoinSyncGroup(name.clone())],
+            CliAction::LeaveSyncGroup => vec![Action::LeaveSyncGroup],
+            CliAction::NewPane { direction } => vec![Action::NewPane(direction.clone())],
+            CliAction::ClosePane => vec![Action::CloseFocus],
+            CliAction::NewTab => vec![Action::NewTab],
+            CliAction::GoToNextTab => vec![Action::GoToNextTab],
+            CliAction::GoToPreviousTab => vec![Action::GoToPreviousTab],
+            CliAction::CloseTab => vec![Action::CloseTab],
+            CliAction::GoToTab { tab_position } => vec![Action::GoToTab(*tab_position)],
+            CliAction::MoveTab { direction } => vec![Action::MoveTab(direction.clone())],
+            CliAction::MoveTabToPosition { tab_position } => {
+                vec![Action::MoveTabToPosition(*tab_position)]
+            }
+            CliAction::RenameTab { name } => vec![
+                // clears the name of the tab
+                Action::TabNameInput(vec![0]),
+                Action::TabNameInput(name.as_bytes().to_vec()),
+            ],
+            CliAction::DumpLayout { path } => {
+                // the session may run in another directory than this command
+                let path = std::env::current_dir()
+                    .map(|current_dir| current_dir.join(path))
+                    .unwrap_or_else(|_| path.clone());
+                vec![Action::DumpLayout(path)]
+            }
+            CliAction::RenameSession { name } => vec![Action::RenameSession(name.clone())],
+            CliAction::Quit => vec![Action::Quit],
+        }
+    }
 }
 
 #[derive(Debug, StructOpt, Clone, Serialize, Deserialize)]
 pub enum Sessions {
     /// List active sessions
     #[structopt(alias = "ls")]
-    ListSessions,
+    ListSessions {
+        /// Print the sessions as JSON, for scripts
+        #[structopt(long)]
+        json: bool,
+    },
 
     /// Attach to session
     #[structopt(alias = "a")]
@@ -75,8 +430,33 @@ pub enum Sessions {
         session_name: String,
 
         /// Force attach- session will detach from the other
-        /// zellij client (if any) and attach to this.
-        #[structopt(long, short)]
+        /// zellij clients (if any) and attach to this.
+        #[structopt(long, short, conflicts_with = "read_only")]
         force: bool,
+
+        /// Watch the session without being able to interact
+        /// with it, except for detaching.
+        #[structopt(long)]
+        read_only: bool,
+
+        /// If the session is not running, start it again from
+        /// where it was last saved.
+        #[structopt(long)]
+        resurrect: bool,
+    },
+
+    /// Kill the specific session
+    #[structopt(alias = "k")]
+    KillSession {
+        /// Name of target session
+        target_session: String,
     },
+
+    /// Kill all sessions
+    #[structopt(alias = "ka")]
+    KillAllSessions,
 }
+
+#[cfg(test)]
+#[path = "./unit/cli_test.rs"]
+mod cli_test;
@@ -179,6 +179,7 @@ impl Display for ContextType {
 pub enum ScreenContext {
     HandlePtyBytes,
     Render,
+    NewPaneFromFocus,
     NewPane,
     HorizontalSplit,
     VerticalSplit,
@@ -204,17 +205,40 @@ pub enum ScreenContext {
     ClearScroll,
     CloseFocusedPane,
     ToggleActiveSyncTab,
+    JoinSyncGroup,
+    LeaveSyncGroup,
     ToggleActiveTerminalFullscreen,
+    SwapPane,
+    MovePane,
+    RotatePanes,
+    BreakPane,
+    JoinPaneToTab,
+    NextLayout,
+    SelectLayout,
+    EqualizePanes,
+    SetPaneWidth,
+    SetPaneHeight,
+    DumpLayout,
+    QuerySessionInfo,
+    QuerySessionTree,
+    CapturePane,
+    WriteToTerminal,
+    SetPaneName,
     SetSelectable,
     SetInvisibleBorders,
     SetMaxHeight,
     ClosePane,
     ApplyLayout,
+    NewTabForBrokenPane,
+    NewTabFromFocus,
     NewTab,
     SwitchTabNext,
     SwitchTabPrev,
     CloseTab,
     GoToTab,
+    MoveTabLeft,
+    MoveTabRight,
+    MoveTabToPosition,
     UpdateTabName,
     TerminalResize,
     ChangeMode,
@@ -226,7 +250,10 @@ pub enum PtyContext {
     SpawnTerminal,
     SpawnTerminalVertically,
     SpawnTerminalHorizontally,
+    RunCommand,
     NewTab,
+    BreakPane,
+    OpenInitialTabs,
     ClosePane,
     CloseTab,
     Exit,
@@ -262,4 +289,9 @@ pub enum ServerContext {
     Error,
     DetachSession,
     AttachClient,
+    TerminalResize,
+    RemoveClient,
+    RenameSession,
+    QuerySessionInfo,
+    QuerySessionTree,
 }
@@ -1,6 +1,10 @@
 //! Definition of the actions that can be bound to keys.
 
+use super::command::RunCommand;
 use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use std::path::PathBuf;
+use std::str::FromStr;
 use zellij_tile::data::InputMode;
 
 /// The four directions (left, right, up, down).
@@ -12,6 +16,108 @@ pub enum Direction {
     Down,
 }
 
+impl FromStr for Direction {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "left" => Ok(Direction::Left),
+            "right" => Ok(Direction::Right),
+            "up" => Ok(Direction::Up),
+            "down" => Ok(Direction::Down),
+            _ => Err(format!("Invalid direction: {}", s)),
+        }
+    }
+}
+
+/// Preset arrangements the panes of a tab can be re-tiled into.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+pub enum LayoutPreset {
+    /// All panes side by side, from left to right.
+    EvenHorizontal,
+    /// All panes stacked on top of each other.
+    EvenVertical,
+    /// A large main pane on the left, the others stacked on its right.
+    MainVertical,
+    /// A large main pane on top, the others side by side below it.
+    MainHorizontal,
+    /// All panes in a grid of (roughly) equal rows and columns.
+    Tiled,
+}
+
+impl LayoutPreset {
+    /// Returns the preset following this one, wrapping around after the last.
+    pub fn next(self) -> Self {
+        match self {
+            LayoutPreset::EvenHorizontal => LayoutPreset::EvenVertical,
+            LayoutPreset::EvenVertical => LayoutPreset::MainVertical,
+            LayoutPreset::MainVertical => LayoutPreset::MainHorizontal,
+            LayoutPreset::MainHorizontal => LayoutPreset::Tiled,
+            LayoutPreset::Tiled => LayoutPreset::EvenHorizontal,
+        }
+    }
+
+    /// Returns the largest amount of pane columns and rows this preset places
+    /// `pane_count` panes in.
+    pub fn grid_size(self, pane_count: usize) -> (usize, usize) {
+        match self {
+            LayoutPreset::EvenHorizontal => (pane_count, 1),
+            LayoutPreset::EvenVertical => (1, pane_count),
+            LayoutPreset::MainVertical if pane_count > 1 => (2, pane_count - 1),
+            LayoutPreset::MainHorizontal if pane_count > 1 => (pane_count - 1, 2),
+            LayoutPreset::MainVertical | LayoutPreset::MainHorizontal => (1, 1),
+            LayoutPreset::Tiled => {
+                let mut columns = 1;
+                while columns * columns < pane_count {
+                    columns += 1;
+                }
+                let rows = (pane_count + columns - 1) / columns;
+                (columns, rows)
+            }
+        }
+    }
+}
+
+impl FromStr for LayoutPreset {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "even-horizontal" => Ok(LayoutPreset::EvenHorizontal),
+            "even-vertical" => Ok(LayoutPreset::EvenVertical),
+            "main-vertical" => Ok(LayoutPreset::MainVertical),
+            "main-horizontal" => Ok(LayoutPreset::MainHorizontal),
+            "tiled" => Ok(LayoutPreset::Tiled),
+            _ => Err(format!("Invalid layout: {}", s)),
+        }
+    }
+}
+
+/// An exact size to resize a pane to.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+pub enum PaneSize {
+    /// A percentage of the space the pane shares with its neighbours, eg. `70%`.
+    Percent(u8),
+    /// An absolute number of columns or rows, eg. `40`.
+    Fixed(usize),
+}
+
+impl FromStr for PaneSize {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Some(percent) = s.strip_suffix('%') {
+            match percent.parse::<u8>() {
+                Ok(percent) if percent > 0 && percent <= 100 => Ok(PaneSize::Percent(percent)),
+                _ => Err(format!("Invalid percentage: {}", s)),
+            }
+        } else {
+            match s.parse::<usize>() {
+                Ok(size) if size > 0 && u16::try_from(size).is_ok() => Ok(PaneSize::Fixed(size)),
+                _ => Err(format!("Invalid size: {}", s)),
+            }
+        }
+    }
+}
+
 // As these actions are bound to the default config, please
 // do take care when refactoring - or renaming.
 // They might need to be adjusted in the default config
@@ -46,11 +152,44 @@ pub enum Action {
     PageScrollDown,
     /// Toggle between fullscreen focus pane and normal layout.
     ToggleFocusFullscreen,
+    /// Swap the position of the focus pane with the pane in the specified direction.
+    SwapPane(Direction),
+    /// Move the focus pane out of its split, and into a split of the pane in the specified
+    /// direction, on the side it moved towards.
+    MovePane(Direction),
+    /// Rotate the positions of all panes in the current tab forwards.
+    RotatePanes,
+    /// Rotate the positions of all panes in the current tab backwards.
+    RotatePanesBackwards,
+    /// Move the focus pane out of the current tab into a new tab.
+    BreakPane,
+    /// Move the focus pane into the tab at the specified position.
+    JoinPaneToTab(u32),
+    /// Re-tile the panes of the current tab into the next preset layout.
+    NextLayout,
+    /// Re-tile the panes of the current tab into the specified preset layout.
+    SelectLayout(LayoutPreset),
+    /// Give all panes of the current tab an equal share of the space they split.
+    EqualizePanes,
+    /// Resize the focus pane to the specified width.
+    SetPaneWidth(PaneSize),
+    /// Resize the focus pane to the specified height.
+    SetPaneHeight(PaneSize),
+    /// Save the tabs and panes of the session, and what runs in them, to a layout file.
+    DumpLayout(PathBuf),
     /// Toggle between sending text commands to all panes on the current tab and normal mode.
     ToggleActiveSyncTab,
+    /// Make the focus pane a member of the named sync group, whose panes (in any tab) all
+    /// receive the input sent to one of them.
+    JoinSyncGroup(String),
+    /// Remove the focus pane from its sync group.
+    LeaveSyncGroup,
     /// Open a new pane in the specified direction (relative to focus).
     /// If no direction is specified, will try to use the biggest available space.
     NewPane(Option<Direction>),
+    /// Run a command in a new pane, in the specified direction (relative to focus) and
+    /// with the specified name.
+    Run(RunCommand, Option<Direction>, Option<String>),
     /// Close the focus pane.
     CloseFocus,
     /// Create a new tab.
@@ -64,7 +203,16 @@ pub enum Action {
     /// Close the current tab.
     CloseTab,
     GoToTab(u32),
+    /// Move the current tab one position in the specified direction.
+    MoveTab(Direction),
+    /// Move the current tab to the specified position (starting from 1, 0 is taken as 1).
+    MoveTabToPosition(u32),
     TabNameInput(Vec<u8>),
+    /// Rename the session.
+    RenameSession(String),
+    /// Edit the name typed for the session, which is renamed once it is entered (`[10]`). `[0]`
+    /// starts a new name and `[27]` gives up on it.
+    SessionNameInput(Vec<u8>),
     /// Detach session and exit
     Detach,
 }
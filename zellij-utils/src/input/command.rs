@@ -0,0 +1,42 @@
+//! Commands that can be run in a new terminal pane.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// What to run in a newly spawned terminal, instead of the default shell.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub enum TerminalAction {
+    /// Open the file in the editor specified by `EDITOR` (or `VISUAL`).
+    OpenFile(PathBuf),
+    /// Run a command.
+    RunCommand(RunCommand),
+}
+
+/// A command to run in a terminal, along with its arguments, working directory and environment.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct RunCommand {
+    /// The command to run. If none is given, the shell specified by `SHELL` is started.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<PathBuf>,
+    /// Arguments passed to the command.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<String>,
+    /// The directory to run the command in.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<PathBuf>,
+    /// Environment variables to set for the command, in addition to the inherited ones.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub env: HashMap<String, String>,
+    /// Whether to keep the pane open after the command exits, showing its exit status.
+    /// If not set, the `hold_on_exit` option applies.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hold_on_exit: Option<bool>,
+}
+
+impl RunCommand {
+    /// Returns `true` if this runs the default shell with nothing changed.
+    pub fn is_empty(&self) -> bool {
+        self == &RunCommand::default()
+    }
+}
@@ -0,0 +1,1082 @@
+//! The layouts of tabs, and of the tabs a session starts with.
+
+use crate::input::{
+    actions::{LayoutPreset, PaneSize},
+    command::{RunCommand, TerminalAction},
+};
+use crate::pane_size::PositionAndSize;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::env;
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::num::TryFromIntError;
+use std::path::{Path, PathBuf};
+use std::{fs::File, io, io::prelude::*};
+
+type LayoutResult = Result<SessionLayout, LayoutError>;
+
+#[derive(Debug)]
+pub enum LayoutError {
+    // Io error with path context
+    IoPath(io::Error, PathBuf),
+    // Deserialization error, with path context when read from a file
+    Serde(serde_yaml::Error, Option<PathBuf>),
+    // A percentage that is not between 1 and 100
+    InvalidPercent(u8),
+    // A fixed size of 0 columns or rows
+    InvalidFixedSize,
+    // The percentages of the parts of a split add up to more than 100
+    PercentagesOver100(usize),
+    // The percentages of the parts of a split add up to less than 100, leaving empty space
+    PercentagesUnder100(usize),
+    // A tab or a split without any panes
+    NoPanes,
+    // A `{{variable}}` that is neither given on the command line nor set in the environment
+    UndefinedVariable(String),
+    // More columns (for a vertical split) or rows (for a horizontal split) are needed than
+    // are available
+    DoesNotFit {
+        direction: Direction,
+        needed: usize,
+        available: usize,
+    },
+}
+
+impl Display for LayoutError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LayoutError::IoPath(ref err, ref path) => {
+                write!(formatter, "IoError: {}, File: {}", err, path.display())
+            }
+            LayoutError::Serde(ref err, Some(ref path)) => {
+                write!(
+                    formatter,
+                    "Deserialization error in {}: {}",
+                    path.display(),
+                    err
+                )
+            }
+            LayoutError::Serde(ref err, None) => {
+                write!(formatter, "Deserialization error: {}", err)
+            }
+            LayoutError::InvalidPercent(percent) => write!(
+                formatter,
+                "Invalid split size: Percent: {} is not between 1 and 100",
+                percent
+            ),
+            LayoutError::InvalidFixedSize => {
+                write!(
+                    formatter,
+                    "Invalid split size: Fixed: 0 leaves no room for the pane"
+                )
+            }
+            LayoutError::PercentagesOver100(total) => write!(
+                formatter,
+                "The parts of a split add up to {}%, more than the 100% available",
+                total
+            ),
+            LayoutError::PercentagesUnder100(total) => write!(
+                formatter,
+                "The parts of a split add up to {}%, leaving the rest of the space empty",
+                total
+            ),
+            LayoutError::NoPanes => write!(formatter, "A tab or split has no parts"),
+            LayoutError::UndefinedVariable(ref name) => write!(
+                formatter,
+                "The variable {{{{{0}}}}} is not defined, pass it with --var {0}=<value> or set it in the environment",
+                name
+            ),
+            LayoutError::DoesNotFit {
+                direction,
+                needed,
+                available,
+            } => {
+                let unit = match direction {
+                    Direction::Vertical => "columns",
+                    Direction::Horizontal => "rows",
+                };
+                write!(
+                    formatter,
+                    "The layout needs {} {} but only {} are available",
+                    needed, unit, available
+                )
+            }
+        }
+    }
+}
+
+impl Error for LayoutError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            LayoutError::IoPath(ref err, _) => Some(err),
+            LayoutError::Serde(ref err, _) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// Replaces every `{{name}}` in the string nodes of a parsed layout with the value of `name` in
+/// `variables`, or else with that of the environment variable `name`.
+///
+/// The values are substituted into the string nodes rather than into the text of the layout, so
+/// they cannot change its structure whatever characters they contain. A placeholder that is a
+/// whole unquoted node becomes a number or a boolean if its value is one, and a string otherwise.
+/// A `\{{` is kept as a literal `{{`.
+pub fn substitute_variables(
+    value: &mut serde_yaml::Value,
+    variables: &HashMap<String, String>,
+) -> Result<(), LayoutError> {
+    if let Some(name) = unquoted_placeholder(value) {
+        *value = plain_scalar(variable_value(&name, variables)?);
+        return Ok(());
+    }
+    match value {
+        serde_yaml::Value::String(string) => {
+            *string = substitute_variables_in_str(string, variables)?;
+        }
+        serde_yaml::Value::Sequence(sequence) => {
+            for value in sequence {
+                substitute_variables(value, variables)?;
+            }
+        }
+        serde_yaml::Value::Mapping(mapping) => {
+            *mapping = mapping
+                .iter()
+                .map(|(key, value)| {
+                    let (mut key, mut value) = (key.clone(), value.clone());
+                    substitute_variables(&mut key, variables)?;
+                    substitute_variables(&mut value, variables)?;
+                    Ok((key, value))
+                })
+                .collect::<Result<_, LayoutError>>()?;
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn substitute_variables_in_str(
+    string: &str,
+    variables: &HashMap<String, String>,
+) -> Result<String, LayoutError> {
+    // a `\{{` is a literal `{{` rather than the start of a placeholder
+    let parts = string
+        .split("\\{{")
+        .map(|part| replace_placeholders(part, |name| variable_value(name, variables)))
+        .collect::<Result<Vec<String>, LayoutError>>()?;
+    Ok(parts.join("{{"))
+}
+
+// the reverse of `substitute_variables` when no variables are set, so that the strings of a
+// serialized layout are the same once it is loaded again
+fn escape_placeholders(value: &mut serde_yaml::Value) {
+    match value {
+        serde_yaml::Value::String(string) => *string = string.replace("{{", "\\{{"),
+        serde_yaml::Value::Sequence(sequence) => sequence.iter_mut().for_each(escape_placeholders),
+        serde_yaml::Value::Mapping(mapping) => {
+            *mapping = mapping
+                .iter()
+                .map(|(key, value)| {
+                    let (mut key, mut value) = (key.clone(), value.clone());
+                    escape_placeholders(&mut key);
+                    escape_placeholders(&mut value);
+                    (key, value)
+                })
+                .collect();
+        }
+        _ => {}
+    }
+}
+
+fn replace_placeholders(
+    string: &str,
+    mut value_of: impl FnMut(&str) -> Result<String, LayoutError>,
+) -> Result<String, LayoutError> {
+    let mut substituted = String::with_capacity(string.len());
+    let mut rest = string;
+    while let Some(start) = rest.find("{{") {
+        let end = match rest[start..].find("}}") {
+            Some(end) => start + end,
+            None => break,
+        };
+        substituted.push_str(&rest[..start]);
+        substituted.push_str(&value_of(rest[start + 2..end].trim())?);
+        rest = &rest[end + 2..];
+    }
+    substituted.push_str(rest);
+    Ok(substituted)
+}
+
+fn variable_value(name: &str, variables: &HashMap<String, String>) -> Result<String, LayoutError> {
+    match variables.get(name) {
+        Some(value) => Ok(value.clone()),
+        None => env::var(name).map_err(|_| LayoutError::UndefinedVariable(name.into())),
+    }
+}
+
+// YAML reads an unquoted `{{name}}` as a mapping whose only key is the mapping `{name: ~}`
+fn unquoted_placeholder(value: &serde_yaml::Value) -> Option<String> {
+    let only_entry = |value: &serde_yaml::Value| {
+        let mapping = value.as_mapping()?;
+        let (key, value) = mapping.iter().next()?;
+        if mapping.len() == 1 && value.is_null() {
+            Some(key.clone())
+        } else {
+            None
+        }
+    };
+    let name = only_entry(&only_entry(value)?)?;
+    name.as_str().map(|name| name.trim().to_string())
+}
+
+fn plain_scalar(value: String) -> serde_yaml::Value {
+    match serde_yaml::from_str(&value) {
+        Ok(scalar @ serde_yaml::Value::Number(_)) | Ok(scalar @ serde_yaml::Value::Bool(_)) => {
+            scalar
+        }
+        _ => serde_yaml::Value::String(value),
+    }
+}
+
+// errors from `serde_yaml::from_value` have no position, so look for the same error in the text of
+// the layout, with every placeholder masked by a string (which cannot change its structure)
+fn locate_error(layout: &str, error: serde_yaml::Error) -> serde_yaml::Error {
+    let masked_layout = replace_placeholders(layout, |_| Ok("variable".to_string())).unwrap();
+    match SessionLayout::from_yaml(&masked_layout) {
+        Err(LayoutError::Serde(located, _))
+            if located.location().is_some() && located.to_string().contains(&error.to_string()) =>
+        {
+            located
+        }
+        _ => error,
+    }
+}
+
+// the share of the tab given to the main pane in the `MainVertical` and `MainHorizontal` presets
+const MAIN_PANE_PERCENT: u8 = 60;
+
+fn split_space_to_parts_vertically(
+    space_to_split: &PositionAndSize,
+    sizes: Vec<Option<SplitSize>>,
+) -> Result<Vec<PositionAndSize>, LayoutError> {
+    let mut split_parts = Vec::new();
+    let mut current_x_position = space_to_split.x;
+    let mut current_width = 0;
+    let gaps = sizes.len() - 1;
+    let does_not_fit = |needed_width: usize| LayoutError::DoesNotFit {
+        direction: Direction::Vertical,
+        needed: needed_width + gaps,
+        available: space_to_split.columns,
+    };
+    let max_width = space_to_split
+        .columns
+        .checked_sub(gaps) // minus space for gaps
+        .ok_or_else(|| does_not_fit(sizes.len()))?;
+
+    let mut parts_to_grow = Vec::new();
+
+    // First fit in the parameterized sizes
+    for size in sizes {
+        let (columns, max_columns) = match size {
+            Some(SplitSize::Percent(percent)) => (
+                std::cmp::max(1, (max_width as f32 * (percent as f32 / 100.0)) as usize),
+                None,
+            ), // TODO: round properly
+            Some(SplitSize::Fixed(size)) => (size as usize, Some(size as usize)),
+            None => {
+                parts_to_grow.push(split_parts.len());
+                (
+                    1, // This is grown later on
+                    None,
+                )
+            }
+        };
+        split_parts.push(PositionAndSize {
+            x: current_x_position,
+            y: space_to_split.y,
+            columns,
+            rows: space_to_split.rows,
+            max_columns,
+            ..Default::default()
+        });
+        current_width += columns;
+        current_x_position += columns + 1; // 1 for gap
+    }
+
+    if current_width > max_width {
+        return Err(does_not_fit(current_width));
+    }
+
+    let mut last_flexible_index = split_parts.len() - 1;
+    // the parts to grow share the space left, including the 1 each of them already takes
+    if let Some(new_columns) =
+        (max_width - current_width + parts_to_grow.len()).checked_div(parts_to_grow.len())
+    {
+        current_width = 0;
+        current_x_position = space_to_split.x;
+        for (idx, part) in split_parts.iter_mut().enumerate() {
+            part.x = current_x_position;
+            if parts_to_grow.contains(&idx) {
+                part.columns = new_columns;
+                last_flexible_index = idx;
+            }
+            current_width += part.columns;
+            current_x_position += part.columns + 1; // 1 for gap
+        }
+    }
+
+    if current_width < max_width {
+        // we have some extra space left, let's add it to the last flexible part
+        let extra = max_width - current_width;
+        let mut last_part = split_parts.get_mut(last_flexible_index).unwrap();
+        last_part.columns += extra;
+        for part in (&mut split_parts[last_flexible_index + 1..]).iter_mut() {
+            part.x += extra;
+        }
+    }
+    Ok(split_parts)
+}
+
+fn split_space_to_parts_horizontally(
+    space_to_split: &PositionAndSize,
+    sizes: Vec<Option<SplitSize>>,
+) -> Result<Vec<PositionAndSize>, LayoutError> {
+    let mut split_parts = Vec::new();
+    let mut current_y_position = space_to_split.y;
+    let mut current_height = 0;
+    let gaps = sizes.len() - 1;
+    let does_not_fit = |needed_height: usize| LayoutError::DoesNotFit {
+        direction: Direction::Horizontal,
+        needed: needed_height + gaps,
+        available: space_to_split.rows,
+    };
+    let max_height = space_to_split
+        .rows
+        .checked_sub(gaps) // minus space for gaps
+        .ok_or_else(|| does_not_fit(sizes.len()))?;
+
+    let mut parts_to_grow = Vec::new();
+
+    for size in sizes {
+        let (rows, max_rows) = match size {
+            Some(SplitSize::Percent(percent)) => (
+                std::cmp::max(1, (max_height as f32 * (percent as f32 / 100.0)) as usize),
+                None,
+            ), // TODO: round properly
+            Some(SplitSize::Fixed(size)) => (size as usize, Some(size as usize)),
+            None => {
+                parts_to_grow.push(split_parts.len());
+                (
+                    1, // This is grown later on
+                    None,
+                )
+            }
+        };
+        split_parts.push(PositionAndSize {
+            x: space_to_split.x,
+            y: current_y_position,
+            columns: space_to_split.columns,
+            rows,
+            max_rows,
+            ..Default::default()
+        });
+        current_height += rows;
+        current_y_position += rows + 1; // 1 for gap
+    }
+
+    if current_height > max_height {
+        return Err(does_not_fit(current_height));
+    }
+
+    let mut last_flexible_index = split_parts.len() - 1;
+    // the parts to grow share the space left, including the 1 each of them already takes
+    if let Some(new_rows) =
+        (max_height - current_height + parts_to_grow.len()).checked_div(parts_to_grow.len())
+    {
+        current_height = 0;
+        current_y_position = space_to_split.y;
+
+        for (idx, part) in split_parts.iter_mut().enumerate() {
+            part.y = current_y_position;
+            if parts_to_grow.contains(&idx) {
+                part.rows = new_rows;
+                last_flexible_index = idx;
+            }
+            current_height += part.rows;
+            current_y_position += part.rows + 1; // 1 for gap
+        }
+    }
+
+    if current_height < max_height {
+        // we have some extra space left, let's add it to the last flexible part
+        let extra = max_height - current_height;
+        let mut last_part = split_parts.get_mut(last_flexible_index).unwrap();
+        last_part.rows += extra;
+        for part in (&mut split_parts[last_flexible_index + 1..]).iter_mut() {
+            part.y += extra;
+        }
+    }
+    Ok(split_parts)
+}
+
+fn split_space(
+    space_to_split: &PositionAndSize,
+    layout: &Layout,
+) -> Result<Vec<(Layout, PositionAndSize)>, LayoutError> {
+    let mut pane_positions = Vec::new();
+    if layout.parts.is_empty() {
+        return Err(LayoutError::NoPanes);
+    }
+    let sizes: Vec<Option<SplitSize>> = layout.parts.iter().map(|part| part.split_size).collect();
+
+    let split_parts = match layout.direction {
+        Direction::Vertical => split_space_to_parts_vertically(space_to_split, sizes)?,
+        Direction::Horizontal => split_space_to_parts_horizontally(space_to_split, sizes)?,
+    };
+    for (i, part) in layout.parts.iter().enumerate() {
+        let part_position_and_size = split_parts.get(i).unwrap();
+        if !part.parts.is_empty() {
+            let mut part_positions = split_space(&part_position_and_size, part)?;
+            pane_positions.append(&mut part_positions);
+        } else {
+            pane_positions.push((part.clone(), *part_position_and_size));
+        }
+    }
+    Ok(pane_positions)
+}
+
+// groups the panes at `indices` into the columns (for `Direction::Vertical`) or rows (for
+// `Direction::Horizontal`) they can be split into without cutting through any of them
+fn split_groups(
+    positions: &[PositionAndSize],
+    indices: &[usize],
+    direction: &Direction,
+) -> Vec<Vec<usize>> {
+    let start_and_end = |index: &usize| {
+        let position = &positions[*index];
+        match direction {
+            Direction::Vertical => (position.x, position.x + position.columns),
+            Direction::Horizontal => (position.y, position.y + position.rows),
+        }
+    };
+    let mut sorted_indices = indices.to_vec();
+    sorted_indices.sort_by_key(start_and_end);
+    let mut groups: Vec<Vec<usize>> = vec![];
+    let mut current_group_end = None;
+    for index in sorted_indices {
+        let (start, end) = start_and_end(&index);
+        match current_group_end {
+            Some(group_end) if start <= group_end => {
+                groups.last_mut().unwrap().push(index);
+                current_group_end = Some(std::cmp::max(group_end, end));
+            }
+            _ => {
+                groups.push(vec![index]);
+                current_group_end = Some(end);
+            }
+        }
+    }
+    groups
+}
+
+fn infer_split(
+    space: &PositionAndSize,
+    positions: &[PositionAndSize],
+    indices: Vec<usize>,
+    pane_order: &mut Vec<usize>,
+) -> Option<Layout> {
+    if indices.len() == 1 {
+        pane_order.push(indices[0]);
+        return Some(Layout::pane(Direction::Horizontal));
+    }
+    for direction in &[Direction::Vertical, Direction::Horizontal] {
+        let groups = split_groups(positions, &indices, direction);
+        if groups.len() < 2 {
+            continue;
+        }
+        let mut parts = vec![];
+        for group in groups {
+            let mut group_space = *space;
+            let start = group.iter().map(|i| match direction {
+                Direction::Vertical => positions[*i].x,
+                Direction::Horizontal => positions[*i].y,
+            });
+            let end = group.iter().map(|i| match direction {
+                Direction::Vertical => positions[*i].x + positions[*i].columns,
+                Direction::Horizontal => positions[*i].y + positions[*i].rows,
+            });
+            let (start, end) = (start.min()?, end.max()?);
+            match direction {
+                Direction::Vertical => {
+                    group_space.x = start;
+                    group_space.columns = end - start;
+                }
+                Direction::Horizontal => {
+                    group_space.y = start;
+                    group_space.rows = end - start;
+                }
+            }
+            let mut part = infer_split(&group_space, positions, group, pane_order)?;
+            part.split_size = Some(SplitSize::Fixed((end - start) as u16));
+            parts.push(part);
+        }
+        return Some(Layout {
+            direction: direction.clone(),
+            parts,
+            split_size: None,
+            plugin: None,
+            focus: None,
+            run: RunCommand::default(),
+        });
+    }
+    // the panes overlap, or are arranged in a way that cannot be split (eg. a pinwheel)
+    None
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub enum Direction {
+    Horizontal,
+    Vertical,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub enum SplitSize {
+    Percent(u8), // 1 to 100
+    Fixed(u16),  // An absolute number of columns or rows
+}
+
+impl TryFrom<PaneSize> for SplitSize {
+    type Error = TryFromIntError;
+    fn try_from(pane_size: PaneSize) -> Result<Self, Self::Error> {
+        match pane_size {
+            PaneSize::Percent(percent) => Ok(SplitSize::Percent(percent)),
+            PaneSize::Fixed(size) => u16::try_from(size).map(SplitSize::Fixed),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Layout {
+    pub direction: Direction,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub parts: Vec<Layout>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub split_size: Option<SplitSize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub plugin: Option<PathBuf>,
+    /// Whether this pane is focused when the layout is applied
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub focus: Option<bool>,
+    /// The `command`, `args`, `cwd` and `env` of a terminal pane
+    #[serde(default, flatten)]
+    pub run: RunCommand,
+}
+
+/// The split tree of a single tab in a [`SessionLayout`].
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TabLayout {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub name: String,
+    /// Whether this tab is the active one once all tabs are opened
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub focus: Option<bool>,
+    pub direction: Direction,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub parts: Vec<Layout>,
+}
+
+impl TabLayout {
+    pub fn layout(&self) -> Layout {
+        Layout {
+            direction: self.direction.clone(),
+            parts: self.parts.clone(),
+            split_size: None,
+            plugin: None,
+            focus: None,
+            run: RunCommand::default(),
+        }
+    }
+}
+
+impl From<Layout> for TabLayout {
+    fn from(layout: Layout) -> Self {
+        TabLayout {
+            name: String::new(),
+            focus: None,
+            direction: layout.direction,
+            parts: layout.parts,
+        }
+    }
+}
+
+/// The tabs a session starts with, and the layout of the tabs opened after them.
+/// A layout file either declares these, or the split tree of a single tab that is used for both.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SessionLayout {
+    pub tabs: Vec<TabLayout>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub template: Option<Layout>,
+}
+
+impl SessionLayout {
+    /// Reads and validates the layout file at `layout_path` (the `.yaml` extension is optional),
+    /// replacing the `{{variables}}` in it with their values (see [`substitute_variables`]).
+    pub fn new(layout_path: &Path, variables: &HashMap<String, String>) -> LayoutResult {
+        let layout_path = if layout_path.exists() {
+            layout_path.to_path_buf()
+        } else {
+            layout_path.with_extension("yaml")
+        };
+        let mut layout_file =
+            File::open(&layout_path).map_err(|e| LayoutError::IoPath(e, layout_path.clone()))?;
+        let mut layout = String::new();
+        layout_file
+            .read_to_string(&mut layout)
+            .map_err(|e| LayoutError::IoPath(e, layout_path.clone()))?;
+        Self::from_yaml_with_variables(&layout, variables).map_err(|e| match e {
+            LayoutError::Serde(e, _) => LayoutError::Serde(e, Some(layout_path)),
+            e => e,
+        })
+    }
+
+    /// Deserializes and validates a layout, either declaring several tabs or being the split
+    /// tree of a single tab.
+    pub fn from_yaml(layout: &str) -> LayoutResult {
+        let is_multi_tab = serde_yaml::from_str::<serde_yaml::Value>(layout)
+            .map(|value| value.get("tabs").is_some())
+            .unwrap_or(false);
+        let session_layout = if is_multi_tab {
+            serde_yaml::from_str(layout).map_err(|e| LayoutError::Serde(e, None))?
+        } else {
+            serde_yaml::from_str(layout)
+                .map(Self::from_single_tab)
+                .map_err(|e| LayoutError::Serde(e, None))?
+        };
+        session_layout.validate()?;
+        Ok(session_layout)
+    }
+
+    /// Like [`SessionLayout::from_yaml`], replacing the `{{variables}}` in the layout with their
+    /// values first (see [`substitute_variables`]).
+    pub fn from_yaml_with_variables(
+        layout: &str,
+        variables: &HashMap<String, String>,
+    ) -> LayoutResult {
+        if !layout.contains("{{") {
+            return Self::from_yaml(layout);
+        }
+        let mut value: serde_yaml::Value =
+            serde_yaml::from_str(layout).map_err(|e| LayoutError::Serde(e, None))?;
+        substitute_variables(&mut value, variables)?;
+        let session_layout = if value.get("tabs").is_some() {
+            serde_yaml::from_value(value)
+        } else {
+            serde_yaml::from_value(value).map(Self::from_single_tab)
+        };
+        let session_layout =
+            session_layout.map_err(|e| LayoutError::Serde(locate_error(layout, e), None))?;
+        session_layout.validate()?;
+        Ok(session_layout)
+    }
+
+    /// Serializes the layout, escaping the `{{` in it so that it is loaded again as it is rather
+    /// than with them taken for variables.
+    pub fn to_yaml(&self) -> Result<String, LayoutError> {
+        let mut value = serde_yaml::to_value(self).map_err(|e| LayoutError::Serde(e, None))?;
+        escape_placeholders(&mut value);
+        serde_yaml::to_string(&value).map_err(|e| LayoutError::Serde(e, None))
+    }
+
+    fn from_single_tab(layout: Layout) -> Self {
+        SessionLayout {
+            tabs: vec![layout.clone().into()],
+            template: Some(layout),
+        }
+    }
+
+    // It wants to use Path here, but that doesn't compile.
+    #[allow(clippy::ptr_arg)]
+    pub fn from_dir(
+        layout: &PathBuf,
+        data_dir: &Path,
+        variables: &HashMap<String, String>,
+    ) -> LayoutResult {
+        Self::new(&data_dir.join("layouts/").join(layout), variables)
+    }
+
+    /// Checks the sizes in the layouts of all tabs and of the template.
+    pub fn validate(&self) -> Result<(), LayoutError> {
+        for layout in self
+            .tabs
+            .iter()
+            .map(TabLayout::layout)
+            .chain(self.template.clone())
+        {
+            if layout.parts.is_empty() {
+                return Err(LayoutError::NoPanes);
+            }
+            layout.validate()?;
+        }
+        Ok(())
+    }
+
+    /// Checks that the layouts of all tabs and of the template fit in a screen of the given size.
+    pub fn check_fits(&self, screen_size: &PositionAndSize) -> Result<(), LayoutError> {
+        for tab in self.tabs.iter() {
+            tab.layout().position_panes_in_space(screen_size)?;
+        }
+        if let Some(template) = &self.template {
+            template.position_panes_in_space(screen_size)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the position of the tab to focus once all tabs are opened, if any is set.
+    pub fn focused_tab_position(&self) -> Option<usize> {
+        self.tabs.iter().position(|tab| tab.focus == Some(true))
+    }
+}
+
+impl Layout {
+    /// Returns what to run in each of the terminal panes of this layout, in the order they are
+    /// positioned (`None` for a plain shell).
+    pub fn terminal_pane_actions(&self) -> Vec<Option<TerminalAction>> {
+        let mut terminal_pane_actions = vec![];
+        for part in self.parts.iter() {
+            if !part.parts.is_empty() {
+                terminal_pane_actions.append(&mut part.terminal_pane_actions());
+            } else if part.plugin.is_none() {
+                if part.run.is_empty() {
+                    terminal_pane_actions.push(None);
+                } else {
+                    terminal_pane_actions.push(Some(TerminalAction::RunCommand(part.run.clone())));
+                }
+            }
+        }
+        terminal_pane_actions
+    }
+
+    /// Returns a [`Layout`] arranging `pane_count` panes according to `preset`.
+    pub fn from_preset(preset: LayoutPreset, pane_count: usize) -> Self {
+        let even_split = |direction: Direction, count: usize| Layout {
+            direction,
+            parts: (0..count)
+                .map(|_| Layout::pane(Direction::Horizontal))
+                .collect(),
+            split_size: None,
+            plugin: None,
+            focus: None,
+            run: RunCommand::default(),
+        };
+        match preset {
+            LayoutPreset::EvenHorizontal => even_split(Direction::Vertical, pane_count),
+            LayoutPreset::EvenVertical => even_split(Direction::Horizontal, pane_count),
+            LayoutPreset::MainVertical | LayoutPreset::MainHorizontal if pane_count > 1 => {
+                let (direction, stack_direction) = match preset {
+                    LayoutPreset::MainVertical => (Direction::Vertical, Direction::Horizontal),
+                    _ => (Direction::Horizontal, Direction::Vertical),
+                };
+                let mut main_pane = Layout::pane(direction.clone());
+                main_pane.split_size = Some(SplitSize::Percent(MAIN_PANE_PERCENT));
+                Layout {
+                    direction,
+                    parts: vec![main_pane, even_split(stack_direction, pane_count - 1)],
+                    split_size: None,
+                    plugin: None,
+                    focus: None,
+                    run: RunCommand::default(),
+                }
+            }
+            LayoutPreset::MainVertical | LayoutPreset::MainHorizontal => {
+                even_split(Direction::Vertical, pane_count)
+            }
+            LayoutPreset::Tiled => {
+                let (columns, rows) = preset.grid_size(pane_count);
+                let mut panes_left = pane_count;
+                let mut grid_rows = vec![];
+                for _ in 0..rows {
+                    let panes_in_row = std::cmp::min(columns, panes_left);
+                    grid_rows.push(even_split(Direction::Vertical, panes_in_row));
+                    panes_left -= panes_in_row;
+                }
+                Layout {
+                    direction: Direction::Horizontal,
+                    parts: grid_rows,
+                    split_size: None,
+                    plugin: None,
+                    focus: None,
+                    run: RunCommand::default(),
+                }
+            }
+        }
+    }
+
+    /// Infers the splits that arrange panes at `positions` inside `space`, returning a [`Layout`]
+    /// that reproduces their geometry when positioned in `space` (all parts have fixed sizes).
+    /// Along with it come the indices of `positions`, in the order their panes are positioned.
+    /// Returns `None` if the panes cannot be described as nested splits.
+    pub fn from_geometry(
+        space: &PositionAndSize,
+        positions: &[PositionAndSize],
+    ) -> Option<(Self, Vec<usize>)> {
+        let mut pane_order = vec![];
+        let layout = infer_split(
+            space,
+            positions,
+            (0..positions.len()).collect(),
+            &mut pane_order,
+        )?;
+        let layout = if layout.parts.is_empty() {
+            // a single pane, which still needs a split to be positioned
+            Layout {
+                direction: Direction::Horizontal,
+                parts: vec![layout],
+                split_size: None,
+                plugin: None,
+                focus: None,
+                run: RunCommand::default(),
+            }
+        } else {
+            layout
+        };
+        Some((layout, pane_order))
+    }
+
+    /// Turns the fixed sizes of the parts of this layout into percentages of the space they split,
+    /// except for the panes that cannot be resized (eg. the tab-bar). `fixed_panes` tells for
+    /// each pane, in the order they are positioned, whether it is one of those.
+    /// The last resizable part of each split takes up the space left, so rounding leaves no gaps.
+    pub fn fixed_sizes_to_percentages(&mut self, fixed_panes: &[bool]) {
+        self.parts_to_percentages(&mut fixed_panes.iter().copied());
+    }
+
+    fn parts_to_percentages(&mut self, fixed_panes: &mut impl Iterator<Item = bool>) {
+        let total_size: usize = self
+            .parts
+            .iter()
+            .map(|part| match part.split_size {
+                Some(SplitSize::Fixed(size)) => size as usize,
+                _ => 0,
+            })
+            .sum();
+        let mut keeps_fixed_size = vec![];
+        for part in self.parts.iter_mut() {
+            if part.parts.is_empty() {
+                keeps_fixed_size.push(fixed_panes.next().unwrap_or(false));
+            } else {
+                part.parts_to_percentages(fixed_panes);
+                keeps_fixed_size.push(false);
+            }
+        }
+        let last_resizable_part = keeps_fixed_size.iter().rposition(|fixed| !fixed);
+        for (i, part) in self.parts.iter_mut().enumerate() {
+            if keeps_fixed_size[i] {
+                continue;
+            }
+            if Some(i) == last_resizable_part {
+                part.split_size = None;
+            } else if let Some(SplitSize::Fixed(size)) = part.split_size {
+                let percent = size as usize * 100 / std::cmp::max(total_size, 1);
+                part.split_size = Some(SplitSize::Percent(std::cmp::max(percent, 1) as u8));
+            }
+        }
+    }
+
+    /// Returns the panes (the parts without parts of their own) of this layout, in the order
+    /// they are positioned.
+    pub fn panes_mut(&mut self) -> Vec<&mut Layout> {
+        if self.parts.is_empty() {
+            return vec![self];
+        }
+        self.parts
+            .iter_mut()
+            .flat_map(|part| part.panes_mut())
+            .collect()
+    }
+
+    /// Gives all parts of this layout, at every level, an equal share of the space they split.
+    pub fn equalize(&mut self) {
+        for part in self.parts.iter_mut() {
+            part.split_size = None;
+            part.equalize();
+        }
+    }
+
+    /// Resizes the `pane_index`th pane of this layout (in the order panes are positioned) to
+    /// `size` in the given `direction`, taking the difference from or giving it to its siblings.
+    /// `Percent` sizes are relative to the space the pane shares with its siblings.
+    /// This requires every part to have a `Fixed` size, as in layouts from [`Layout::from_geometry`].
+    /// Returns `false` if the pane could not be resized.
+    pub fn resize_pane(
+        &mut self,
+        pane_index: usize,
+        direction: Direction,
+        size: SplitSize,
+        min_pane_size: usize,
+    ) -> bool {
+        let mut path = vec![];
+        let mut panes_to_skip = pane_index;
+        if !self.path_to_pane(&mut panes_to_skip, &mut path) {
+            return false;
+        }
+        // the innermost split in the requested direction is the one the pane is resized in
+        for depth in (0..path.len()).rev() {
+            let split = self.part_at_mut(&path[..depth]);
+            if split.direction == direction && split.parts.len() > 1 {
+                return split.resize_part(path[depth], size, min_pane_size);
+            }
+        }
+        false
+    }
+
+    fn path_to_pane(&self, panes_to_skip: &mut usize, path: &mut Vec<usize>) -> bool {
+        for (i, part) in self.parts.iter().enumerate() {
+            path.push(i);
+            if part.parts.is_empty() {
+                if *panes_to_skip == 0 {
+                    return true;
+                }
+                *panes_to_skip -= 1;
+            } else if part.path_to_pane(panes_to_skip, path) {
+                return true;
+            }
+            path.pop();
+        }
+        false
+    }
+
+    fn part_at_mut(&mut self, path: &[usize]) -> &mut Layout {
+        match path.split_first() {
+            Some((i, rest)) => self.parts[*i].part_at_mut(rest),
+            None => self,
+        }
+    }
+
+    // the smallest amount of columns/rows this part can take in the given direction
+    fn min_size(&self, direction: &Direction, min_pane_size: usize) -> usize {
+        let part_sizes = self
+            .parts
+            .iter()
+            .map(|part| part.min_size(direction, min_pane_size));
+        if self.parts.is_empty() {
+            min_pane_size
+        } else if &self.direction == direction {
+            part_sizes.sum::<usize>() + self.parts.len() - 1 // 1 for each gap
+        } else {
+            part_sizes.max().unwrap()
+        }
+    }
+
+    fn resize_part(&mut self, part_index: usize, size: SplitSize, min_pane_size: usize) -> bool {
+        let mut sizes = vec![];
+        for part in self.parts.iter() {
+            match part.split_size {
+                Some(SplitSize::Fixed(size)) => sizes.push(size as usize),
+                _ => return false,
+            }
+        }
+        let min_sizes: Vec<usize> = self
+            .parts
+            .iter()
+            .map(|part| part.min_size(&self.direction, min_pane_size))
+            .collect();
+        let total: usize = sizes.iter().sum();
+        let min_total: usize = min_sizes.iter().sum();
+        if min_total > total {
+            return false;
+        }
+        let requested_size = match size {
+            SplitSize::Percent(percent) => total * percent as usize / 100,
+            SplitSize::Fixed(size) => size as usize,
+        };
+        let new_size = std::cmp::min(
+            std::cmp::max(requested_size, min_sizes[part_index]),
+            total - (min_total - min_sizes[part_index]),
+        );
+        // the siblings share what is left in proportion to their current sizes
+        let siblings_total = total - sizes[part_index];
+        let new_siblings_total = total - new_size;
+        let mut new_sizes: Vec<usize> = sizes
+            .iter()
+            .zip(min_sizes.iter())
+            .map(|(size, min_size)| {
+                let size = size * new_siblings_total / std::cmp::max(siblings_total, 1);
+                std::cmp::max(size, *min_size)
+            })
+            .collect();
+        new_sizes[part_index] = new_size;
+        // make up for rounding and for siblings that could not shrink below their minimum
+        let mut difference = total as isize - new_sizes.iter().sum::<usize>() as isize;
+        for i in (0..new_sizes.len()).rev().filter(|i| *i != part_index) {
+            if difference > 0 {
+                new_sizes[i] += difference as usize;
+                difference = 0;
+            } else if difference < 0 {
+                let can_shrink_by = (new_sizes[i] - min_sizes[i]) as isize;
+                let shrink_by = std::cmp::min(can_shrink_by, -difference);
+                new_sizes[i] -= shrink_by as usize;
+                difference += shrink_by;
+            }
+        }
+        for (part, size) in self.parts.iter_mut().zip(new_sizes) {
+            part.split_size = Some(SplitSize::Fixed(size as u16));
+        }
+        true
+    }
+
+    fn pane(direction: Direction) -> Self {
+        Layout {
+            direction,
+            parts: vec![],
+            split_size: None,
+            plugin: None,
+            focus: None,
+            run: RunCommand::default(),
+        }
+    }
+
+    /// Splits `space` into the panes of this layout, returning each pane along with its
+    /// position and size. Fails if the panes do not fit in `space`.
+    pub fn position_panes_in_space(
+        &self,
+        space: &PositionAndSize,
+    ) -> Result<Vec<(Layout, PositionAndSize)>, LayoutError> {
+        split_space(space, &self)
+    }
+
+    /// Checks the sizes of the parts of this layout, at every level.
+    pub fn validate(&self) -> Result<(), LayoutError> {
+        let mut total_percent = 0;
+        let mut has_flexible_part = false;
+        for part in self.parts.iter() {
+            match part.split_size {
+                Some(SplitSize::Percent(percent)) if percent == 0 || percent > 100 => {
+                    return Err(LayoutError::InvalidPercent(percent));
+                }
+                Some(SplitSize::Percent(percent)) => total_percent += percent as usize,
+                Some(SplitSize::Fixed(0)) => return Err(LayoutError::InvalidFixedSize),
+                Some(SplitSize::Fixed(_)) => {}
+                None => has_flexible_part = true,
+            }
+            part.validate()?;
+        }
+        if total_percent > 100 {
+            return Err(LayoutError::PercentagesOver100(total_percent));
+        }
+        let only_percentages = !self.parts.is_empty()
+            && self
+                .parts
+                .iter()
+                .all(|part| matches!(part.split_size, Some(SplitSize::Percent(_))));
+        if only_percentages && total_percent < 100 && !has_flexible_part {
+            return Err(LayoutError::PercentagesUnder100(total_percent));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[path = "./unit/layout_test.rs"]
+mod layout_test;
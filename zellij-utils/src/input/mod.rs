@@ -1,8 +1,10 @@
 //! The way terminal input is handled.
 
 pub mod actions;
+pub mod command;
 pub mod config;
 pub mod keybinds;
+pub mod layout;
 pub mod options;
 
 use termion::input::TermRead;
@@ -21,6 +23,7 @@ pub fn get_mode_info(
         InputMode::Normal | InputMode::Locked => {}
         InputMode::Resize => {
             keybinds.push(("←↓↑→".to_string(), "Resize".to_string()));
+            keybinds.push(("=".to_string(), "Equalize".to_string()));
         }
         InputMode::Pane => {
             keybinds.push(("←↓↑→".to_string(), "Move focus".to_string()));
@@ -30,6 +33,11 @@ pub fn get_mode_info(
             keybinds.push(("r".to_string(), "Right split".to_string()));
             keybinds.push(("x".to_string(), "Close".to_string()));
             keybinds.push(("f".to_string(), "Fullscreen".to_string()));
+            keybinds.push(("HJKL".to_string(), "Swap".to_string()));
+            keybinds.push(("m".to_string(), "Move".to_string()));
+            keybinds.push(("o".to_string(), "Rotate".to_string()));
+            keybinds.push(("b".to_string(), "Break".to_string()));
+            keybinds.push(("a".to_string(), "Layout".to_string()));
         }
         InputMode::Tab => {
             keybinds.push(("←↓↑→".to_string(), "Move focus".to_string()));
@@ -37,6 +45,7 @@ pub fn get_mode_info(
             keybinds.push(("x".to_string(), "Close".to_string()));
             keybinds.push(("r".to_string(), "Rename".to_string()));
             keybinds.push(("s".to_string(), "Sync".to_string()));
+            keybinds.push(("HL".to_string(), "Move".to_string()));
         }
         InputMode::Scroll => {
             keybinds.push(("↓↑".to_string(), "Scroll".to_string()));
@@ -47,6 +56,10 @@ pub fn get_mode_info(
         }
         InputMode::Session => {
             keybinds.push(("d".to_string(), "Detach".to_string()));
+            keybinds.push(("r".to_string(), "Rename".to_string()));
+        }
+        InputMode::RenameSession => {
+            keybinds.push(("Enter".to_string(), "when done".to_string()));
         }
     }
     ModeInfo {
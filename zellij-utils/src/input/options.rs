@@ -11,6 +11,11 @@ pub struct Options {
     /// that is compatible with more fonts
     #[structopt(long)]
     pub simplified_ui: bool,
+    /// Keep the panes of commands open after they exit,
+    /// showing their exit status
+    #[structopt(long)]
+    #[serde(default)]
+    pub hold_on_exit: bool,
 }
 
 impl Options {
@@ -32,7 +37,16 @@ impl Options {
             self.simplified_ui
         };
 
-        Options { simplified_ui }
+        let hold_on_exit = if other.hold_on_exit {
+            true
+        } else {
+            self.hold_on_exit
+        };
+
+        Options {
+            simplified_ui,
+            hold_on_exit,
+        }
     }
 
     pub fn from_cli(&self, other: Option<Command>) -> Options {
@@ -0,0 +1,554 @@
+use super::super::layout::*;
+use crate::input::actions::{LayoutPreset, PaneSize};
+use crate::pane_size::PositionAndSize;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::path::PathBuf;
+
+fn screen(columns: usize, rows: usize) -> PositionAndSize {
+    PositionAndSize {
+        columns,
+        rows,
+        ..Default::default()
+    }
+}
+
+fn position(x: usize, y: usize, columns: usize, rows: usize) -> PositionAndSize {
+    PositionAndSize {
+        x,
+        y,
+        columns,
+        rows,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn single_tab_layout_is_also_the_template() {
+    let layout = "
+direction: Horizontal
+parts:
+  - direction: Vertical
+    split_size:
+      Percent: 30
+  - direction: Vertical
+";
+    let session_layout = SessionLayout::from_yaml(layout).unwrap();
+    assert_eq!(session_layout.tabs.len(), 1);
+    assert!(session_layout.template.is_some());
+}
+
+#[test]
+fn multi_tab_layout_with_focused_tab() {
+    let layout = "
+tabs:
+  - name: editor
+    direction: Vertical
+    parts:
+      - direction: Horizontal
+  - name: logs
+    focus: true
+    direction: Vertical
+    parts:
+      - direction: Horizontal
+        command: tail
+        args: [-f, log.txt]
+";
+    let session_layout = SessionLayout::from_yaml(layout).unwrap();
+    assert_eq!(session_layout.tabs.len(), 2);
+    assert_eq!(session_layout.tabs[1].name, "logs");
+    assert_eq!(session_layout.focused_tab_position(), Some(1));
+    assert!(session_layout.template.is_none());
+}
+
+#[test]
+fn parse_error_is_not_a_panic() {
+    let layout = "
+direction: Horizontal
+parts:
+  - direction: Diagonal
+";
+    match SessionLayout::from_yaml(layout) {
+        Err(LayoutError::Serde(..)) => {}
+        other => panic!("expected a deserialization error, got {:?}", other),
+    }
+}
+
+#[test]
+fn percentages_over_100_are_an_error() {
+    let layout = "
+direction: Horizontal
+parts:
+  - direction: Vertical
+    split_size:
+      Percent: 20
+  - direction: Vertical
+    split_size:
+      Percent: 90
+";
+    match SessionLayout::from_yaml(layout) {
+        Err(LayoutError::PercentagesOver100(110)) => {}
+        other => panic!("expected percentages over 100, got {:?}", other),
+    }
+}
+
+#[test]
+fn percentages_under_100_without_flexible_part_are_an_error() {
+    let layout = "
+direction: Horizontal
+parts:
+  - direction: Vertical
+    split_size:
+      Percent: 20
+  - direction: Vertical
+    split_size:
+      Percent: 50
+";
+    match SessionLayout::from_yaml(layout) {
+        Err(LayoutError::PercentagesUnder100(70)) => {}
+        other => panic!("expected percentages under 100, got {:?}", other),
+    }
+}
+
+#[test]
+fn fixed_sizes_that_do_not_fit_the_screen_are_an_error() {
+    let layout = "
+direction: Vertical
+parts:
+  - direction: Horizontal
+    split_size:
+      Fixed: 50
+  - direction: Horizontal
+";
+    let session_layout = SessionLayout::from_yaml(layout).unwrap();
+    assert!(session_layout.check_fits(&screen(80, 20)).is_ok());
+    match session_layout.check_fits(&screen(40, 20)) {
+        Err(LayoutError::DoesNotFit {
+            needed: 52,
+            available: 40,
+            ..
+        }) => {}
+        other => panic!("expected the layout not to fit, got {:?}", other),
+    }
+}
+
+#[test]
+fn layout_from_geometry_reproduces_pane_positions() {
+    let space = screen(80, 20);
+    // one pane on the left, two stacked on the right
+    let positions = vec![
+        position(41, 11, 39, 9),
+        position(0, 0, 40, 20),
+        position(41, 0, 39, 10),
+    ];
+    let (layout, pane_order) = Layout::from_geometry(&space, &positions).unwrap();
+    assert_eq!(pane_order, vec![1, 2, 0]);
+    let positions_in_layout: Vec<PositionAndSize> = layout
+        .position_panes_in_space(&space)
+        .unwrap()
+        .into_iter()
+        .map(|(_, position_and_size)| position_and_size)
+        .collect();
+    for (position_in_layout, pane_index) in positions_in_layout.iter().zip(pane_order) {
+        let expected = positions[pane_index];
+        assert_eq!(
+            (
+                position_in_layout.x,
+                position_in_layout.y,
+                position_in_layout.columns,
+                position_in_layout.rows
+            ),
+            (expected.x, expected.y, expected.columns, expected.rows)
+        );
+    }
+}
+
+#[test]
+fn resize_pane_to_percentage_of_its_split() {
+    let space = screen(81, 20);
+    let positions = vec![position(0, 0, 40, 20), position(41, 0, 40, 20)];
+    let (mut layout, _) = Layout::from_geometry(&space, &positions).unwrap();
+    assert!(layout.resize_pane(0, Direction::Vertical, SplitSize::Percent(70), 4));
+    let columns: Vec<usize> = layout
+        .position_panes_in_space(&space)
+        .unwrap()
+        .into_iter()
+        .map(|(_, position_and_size)| position_and_size.columns)
+        .collect();
+    assert_eq!(columns, vec![56, 24]);
+}
+
+#[test]
+fn resize_pane_to_fixed_size() {
+    let space = screen(81, 20);
+    let positions = vec![position(0, 0, 40, 20), position(41, 0, 40, 20)];
+    let (mut layout, _) = Layout::from_geometry(&space, &positions).unwrap();
+    let split_size = SplitSize::try_from(PaneSize::Fixed(30)).unwrap();
+    assert!(layout.resize_pane(0, Direction::Vertical, split_size, 4));
+    let columns: Vec<usize> = layout
+        .position_panes_in_space(&space)
+        .unwrap()
+        .into_iter()
+        .map(|(_, position_and_size)| position_and_size.columns)
+        .collect();
+    assert_eq!(columns, vec![30, 50]);
+}
+
+#[test]
+fn fixed_sizes_that_do_not_fit_in_a_split_size_are_rejected() {
+    let too_large = u16::MAX as usize + 1;
+    assert!(SplitSize::try_from(PaneSize::Fixed(too_large)).is_err());
+    assert!(too_large.to_string().parse::<PaneSize>().is_err());
+    assert_eq!("40".parse::<PaneSize>(), Ok(PaneSize::Fixed(40)));
+    assert_eq!("70%".parse::<PaneSize>(), Ok(PaneSize::Percent(70)));
+}
+
+#[test]
+fn equalize_gives_panes_an_equal_share() {
+    let space = screen(81, 20);
+    let positions = vec![position(0, 0, 60, 20), position(61, 0, 20, 20)];
+    let (mut layout, _) = Layout::from_geometry(&space, &positions).unwrap();
+    layout.equalize();
+    let columns: Vec<usize> = layout
+        .position_panes_in_space(&space)
+        .unwrap()
+        .into_iter()
+        .map(|(_, position_and_size)| position_and_size.columns)
+        .collect();
+    assert_eq!(columns, vec![40, 40]);
+}
+
+#[test]
+fn variables_are_substituted_before_deserialization() {
+    let layout = "
+direction: Horizontal
+parts:
+  - direction: Vertical
+    cwd: {{ project_dir }}
+    command: git
+    args: [checkout, '{{branch}}']
+";
+    let variables: HashMap<String, String> = vec![
+        ("project_dir".to_string(), "/srv/payments".to_string()),
+        ("branch".to_string(), "main".to_string()),
+    ]
+    .into_iter()
+    .collect();
+    let session_layout = SessionLayout::from_yaml_with_variables(layout, &variables).unwrap();
+    let pane = &session_layout.tabs[0].parts[0];
+    assert_eq!(pane.run.cwd, Some(PathBuf::from("/srv/payments")));
+    assert_eq!(pane.run.args, vec!["checkout", "main"]);
+}
+
+#[test]
+fn variables_with_special_characters_do_not_change_the_layout() {
+    let layout = "
+direction: Horizontal
+parts:
+  - direction: Vertical
+    cwd: {{dir}}
+    command: echo
+    args: [\"{{text}}\", 'prefix {{text}} suffix']
+";
+    let text = "a: b # c\n- 'd\" {{e}} }} [f, g]";
+    let variables: HashMap<String, String> = vec![
+        ("dir".to_string(), "/srv/[x]: y".to_string()),
+        ("text".to_string(), text.to_string()),
+    ]
+    .into_iter()
+    .collect();
+    let session_layout = SessionLayout::from_yaml_with_variables(layout, &variables).unwrap();
+    assert_eq!(session_layout.tabs[0].parts.len(), 1);
+    let pane = &session_layout.tabs[0].parts[0];
+    assert_eq!(pane.run.cwd, Some(PathBuf::from("/srv/[x]: y")));
+    assert_eq!(
+        pane.run.args,
+        vec![text.to_string(), format!("prefix {} suffix", text)]
+    );
+}
+
+#[test]
+fn unquoted_variables_can_be_numbers() {
+    let layout = "
+direction: Vertical
+parts:
+  - direction: Horizontal
+    split_size:
+      Percent: {{ share }}
+  - direction: Horizontal
+";
+    let variables: HashMap<String, String> = vec![("share".to_string(), "30".to_string())]
+        .into_iter()
+        .collect();
+    let session_layout = SessionLayout::from_yaml_with_variables(layout, &variables).unwrap();
+    assert!(matches!(
+        session_layout.tabs[0].parts[0].split_size,
+        Some(SplitSize::Percent(30))
+    ));
+}
+
+#[test]
+fn undefined_variables_are_an_error() {
+    let layout = "
+direction: Horizontal
+parts:
+  - direction: Vertical
+    cwd: {{zellij_layout_test_undefined_variable}}
+";
+    match SessionLayout::from_yaml_with_variables(layout, &HashMap::new()) {
+        Err(LayoutError::UndefinedVariable(name)) => {
+            assert_eq!(name, "zellij_layout_test_undefined_variable")
+        }
+        other => panic!("expected an undefined variable, got {:?}", other),
+    }
+}
+
+#[test]
+fn errors_in_layouts_with_variables_point_at_their_line() {
+    let layout = "
+direction: Horizontal
+parts:
+  - direction: Vertical
+    cwd: {{dir}}
+    args: ['{{text}}']
+  - direction: Vertical
+    split_size:
+      Percent: lots
+";
+    let variables: HashMap<String, String> = vec![
+        ("dir".to_string(), "/srv".to_string()),
+        ("text".to_string(), "a\nb\nc".to_string()),
+    ]
+    .into_iter()
+    .collect();
+    match SessionLayout::from_yaml_with_variables(layout, &variables) {
+        Err(LayoutError::Serde(e, None)) => {
+            assert_eq!(e.location().map(|location| location.line()), Some(9))
+        }
+        other => panic!("expected a deserialization error, got {:?}", other),
+    }
+}
+
+fn preset_positions(preset: LayoutPreset, pane_count: usize) -> Vec<(usize, usize, usize, usize)> {
+    Layout::from_preset(preset, pane_count)
+        .position_panes_in_space(&screen(121, 40))
+        .unwrap()
+        .into_iter()
+        .map(|(_, p)| (p.x, p.y, p.columns, p.rows))
+        .collect()
+}
+
+#[test]
+fn even_horizontal_preset_places_panes_side_by_side() {
+    assert_eq!(
+        preset_positions(LayoutPreset::EvenHorizontal, 1),
+        vec![(0, 0, 121, 40)]
+    );
+    assert_eq!(
+        preset_positions(LayoutPreset::EvenHorizontal, 2),
+        vec![(0, 0, 60, 40), (61, 0, 60, 40)]
+    );
+    assert_eq!(
+        preset_positions(LayoutPreset::EvenHorizontal, 3),
+        vec![(0, 0, 39, 40), (40, 0, 39, 40), (80, 0, 41, 40)]
+    );
+    assert_eq!(
+        preset_positions(LayoutPreset::EvenHorizontal, 4),
+        vec![
+            (0, 0, 29, 40),
+            (30, 0, 29, 40),
+            (60, 0, 29, 40),
+            (90, 0, 31, 40)
+        ]
+    );
+    assert_eq!(
+        preset_positions(LayoutPreset::EvenHorizontal, 5),
+        vec![
+            (0, 0, 23, 40),
+            (24, 0, 23, 40),
+            (48, 0, 23, 40),
+            (72, 0, 23, 40),
+            (96, 0, 25, 40)
+        ]
+    );
+}
+
+#[test]
+fn even_vertical_preset_stacks_panes() {
+    assert_eq!(
+        preset_positions(LayoutPreset::EvenVertical, 1),
+        vec![(0, 0, 121, 40)]
+    );
+    assert_eq!(
+        preset_positions(LayoutPreset::EvenVertical, 2),
+        vec![(0, 0, 121, 19), (0, 20, 121, 20)]
+    );
+    assert_eq!(
+        preset_positions(LayoutPreset::EvenVertical, 3),
+        vec![(0, 0, 121, 12), (0, 13, 121, 12), (0, 26, 121, 14)]
+    );
+    assert_eq!(
+        preset_positions(LayoutPreset::EvenVertical, 4),
+        vec![
+            (0, 0, 121, 9),
+            (0, 10, 121, 9),
+            (0, 20, 121, 9),
+            (0, 30, 121, 10)
+        ]
+    );
+    assert_eq!(
+        preset_positions(LayoutPreset::EvenVertical, 5),
+        vec![
+            (0, 0, 121, 7),
+            (0, 8, 121, 7),
+            (0, 16, 121, 7),
+            (0, 24, 121, 7),
+            (0, 32, 121, 8)
+        ]
+    );
+}
+
+#[test]
+fn main_vertical_preset_stacks_panes_right_of_the_main_pane() {
+    assert_eq!(
+        preset_positions(LayoutPreset::MainVertical, 1),
+        vec![(0, 0, 121, 40)]
+    );
+    assert_eq!(
+        preset_positions(LayoutPreset::MainVertical, 2),
+        vec![(0, 0, 72, 40), (73, 0, 48, 40)]
+    );
+    assert_eq!(
+        preset_positions(LayoutPreset::MainVertical, 3),
+        vec![(0, 0, 72, 40), (73, 0, 48, 19), (73, 20, 48, 20)]
+    );
+    assert_eq!(
+        preset_positions(LayoutPreset::MainVertical, 4),
+        vec![
+            (0, 0, 72, 40),
+            (73, 0, 48, 12),
+            (73, 13, 48, 12),
+            (73, 26, 48, 14)
+        ]
+    );
+    assert_eq!(
+        preset_positions(LayoutPreset::MainVertical, 5),
+        vec![
+            (0, 0, 72, 40),
+            (73, 0, 48, 9),
+            (73, 10, 48, 9),
+            (73, 20, 48, 9),
+            (73, 30, 48, 10)
+        ]
+    );
+}
+
+#[test]
+fn main_horizontal_preset_places_panes_below_the_main_pane() {
+    assert_eq!(
+        preset_positions(LayoutPreset::MainHorizontal, 1),
+        vec![(0, 0, 121, 40)]
+    );
+    assert_eq!(
+        preset_positions(LayoutPreset::MainHorizontal, 2),
+        vec![(0, 0, 121, 23), (0, 24, 121, 16)]
+    );
+    assert_eq!(
+        preset_positions(LayoutPreset::MainHorizontal, 3),
+        vec![(0, 0, 121, 23), (0, 24, 60, 16), (61, 24, 60, 16)]
+    );
+    assert_eq!(
+        preset_positions(LayoutPreset::MainHorizontal, 4),
+        vec![
+            (0, 0, 121, 23),
+            (0, 24, 39, 16),
+            (40, 24, 39, 16),
+            (80, 24, 41, 16)
+        ]
+    );
+    assert_eq!(
+        preset_positions(LayoutPreset::MainHorizontal, 5),
+        vec![
+            (0, 0, 121, 23),
+            (0, 24, 29, 16),
+            (30, 24, 29, 16),
+            (60, 24, 29, 16),
+            (90, 24, 31, 16)
+        ]
+    );
+}
+
+#[test]
+fn tiled_preset_places_panes_in_a_grid() {
+    assert_eq!(
+        preset_positions(LayoutPreset::Tiled, 1),
+        vec![(0, 0, 121, 40)]
+    );
+    assert_eq!(
+        preset_positions(LayoutPreset::Tiled, 2),
+        vec![(0, 0, 60, 40), (61, 0, 60, 40)]
+    );
+    assert_eq!(
+        preset_positions(LayoutPreset::Tiled, 3),
+        vec![(0, 0, 60, 19), (61, 0, 60, 19), (0, 20, 121, 20)]
+    );
+    assert_eq!(
+        preset_positions(LayoutPreset::Tiled, 4),
+        vec![
+            (0, 0, 60, 19),
+            (61, 0, 60, 19),
+            (0, 20, 60, 20),
+            (61, 20, 60, 20)
+        ]
+    );
+    assert_eq!(
+        preset_positions(LayoutPreset::Tiled, 5),
+        vec![
+            (0, 0, 39, 19),
+            (40, 0, 39, 19),
+            (80, 0, 41, 19),
+            (0, 20, 60, 20),
+            (61, 20, 60, 20)
+        ]
+    );
+}
+
+#[test]
+fn cycling_through_presets_wraps_around() {
+    let mut preset = LayoutPreset::EvenHorizontal;
+    let mut cycled = vec![];
+    for _ in 0..5 {
+        preset = preset.next();
+        cycled.push(preset);
+    }
+    assert_eq!(
+        cycled,
+        vec![
+            LayoutPreset::EvenVertical,
+            LayoutPreset::MainVertical,
+            LayoutPreset::MainHorizontal,
+            LayoutPreset::Tiled,
+            LayoutPreset::EvenHorizontal,
+        ]
+    );
+}
+
+#[test]
+fn braces_in_a_serialized_layout_are_not_taken_for_variables() {
+    let layout = "
+direction: Horizontal
+parts:
+  - direction: Vertical
+    command: docker
+    args: [ps, --format, '\\{{.Names}}', 'a\\b\\{{{c}}']
+";
+    let session_layout = SessionLayout::from_yaml_with_variables(layout, &HashMap::new()).unwrap();
+    let args = vec!["ps", "--format", "{{.Names}}", "a\\b{{{c}}"];
+    assert_eq!(session_layout.tabs[0].parts[0].run.args, args);
+    // saved as it is, and loaded again
+    let saved_layout = session_layout.to_yaml().unwrap();
+    let session_layout =
+        SessionLayout::from_yaml_with_variables(&saved_layout, &HashMap::new()).unwrap();
+    assert_eq!(session_layout.tabs[0].parts[0].run.args, args);
+    assert_eq!(session_layout.template.unwrap().parts[0].run.args, args);
+}
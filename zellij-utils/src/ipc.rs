@@ -3,21 +3,133 @@
 use crate::cli::CliArgs;
 use crate::pane_size::PositionAndSize;
 use crate::{
+    consts::VERSION,
     errors::{get_current_ctx, ErrorContext},
     input::{actions::Action, options::Options},
 };
+use bincode::Options as _;
 use interprocess::local_socket::LocalSocketStream;
 use nix::unistd::dup;
 use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Error, Formatter};
-use std::io::{self, Write};
+use std::io::{self, Read, Write};
 use std::marker::PhantomData;
 use std::os::unix::io::{AsRawFd, FromRawFd};
+use std::path::PathBuf;
 
 use zellij_tile::data::Palette;
 
 type SessionId = u64;
 
+/// Identifies one of the clients connected to a server.
+pub type ClientId = u64;
+
+/// The version of the messages exchanged by clients and servers. It must be bumped whenever
+/// [`ClientToServerMsg`], [`ServerToClientMsg`] or the types they carry change, as bincode
+/// cannot tell a message of another version from a garbled one.
+pub const IPC_PROTOCOL_VERSION: u32 = 3;
+
+/// The optional messages this version of the protocol understands.
+pub const IPC_CAPABILITIES: &[&str] = &[
+    "attach-read-only",
+    "kill-session",
+    "rename-session",
+    "query-session-info",
+    "query-session-tree",
+    "capture-pane",
+    "write-to-pane",
+];
+
+const HANDSHAKE_MAGIC: [u8; 6] = *b"zellij";
+// a garbled handshake must not make us allocate much
+const HANDSHAKE_SIZE_LIMIT: u64 = 4096;
+
+/// What a client and a server first send each other, before any other message, to make sure
+/// they understand each other.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Handshake {
+    magic: [u8; 6],
+    pub protocol_version: u32,
+    /// The version of zellij
+    pub version: String,
+    pub capabilities: Vec<String>,
+}
+
+impl Handshake {
+    /// Returns the handshake of this version of zellij.
+    pub fn current() -> Self {
+        Handshake {
+            magic: HANDSHAKE_MAGIC,
+            protocol_version: IPC_PROTOCOL_VERSION,
+            version: VERSION.to_string(),
+            capabilities: IPC_CAPABILITIES.iter().map(|c| c.to_string()).collect(),
+        }
+    }
+
+    /// Returns whether the other messages of whoever sent this handshake can be understood, and
+    /// whether it understands all of ours.
+    pub fn is_compatible(&self) -> bool {
+        self.protocol_version == IPC_PROTOCOL_VERSION && self.missing_capabilities().is_empty()
+    }
+
+    /// Returns the capabilities of this version of the protocol that whoever sent this
+    /// handshake lacks.
+    pub fn missing_capabilities(&self) -> Vec<String> {
+        IPC_CAPABILITIES
+            .iter()
+            .filter(|capability| !self.capabilities.iter().any(|c| c == *capability))
+            .map(|capability| capability.to_string())
+            .collect()
+    }
+
+    fn send(&self, stream: &mut impl Write) -> io::Result<()> {
+        let bytes = bincode::DefaultOptions::new()
+            .with_limit(HANDSHAKE_SIZE_LIMIT)
+            .serialize(self)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        stream.write_all(&bytes)?;
+        stream.flush()
+    }
+
+    // reads the handshake straight from the stream, so that none of the messages that follow it
+    // are buffered and lost
+    fn recv(stream: &mut impl Read) -> Option<Self> {
+        bincode::DefaultOptions::new()
+            .with_limit(HANDSHAKE_SIZE_LIMIT)
+            .deserialize_from(stream.by_ref())
+            .ok()
+            .filter(|handshake: &Handshake| handshake.magic == HANDSHAKE_MAGIC)
+    }
+}
+
+/// Sends our [`Handshake`] to the server on the other end of `stream`, and checks its answer.
+/// Nothing else must be sent on the stream if this fails.
+pub fn handshake_with_server(stream: &mut (impl Read + Write)) -> Result<(), ExitReason> {
+    let _ = Handshake::current().send(stream);
+    match Handshake::recv(stream) {
+        Some(handshake) if handshake.is_compatible() => Ok(()),
+        Some(handshake) if handshake.protocol_version == IPC_PROTOCOL_VERSION => {
+            let missing_capabilities = handshake.missing_capabilities();
+            Err(ExitReason::MissingCapabilities(
+                handshake.version,
+                missing_capabilities,
+            ))
+        }
+        handshake => Err(ExitReason::IncompatibleVersion(
+            handshake.map(|handshake| handshake.version),
+        )),
+    }
+}
+
+/// Checks the [`Handshake`] of the client on the other end of `stream`, and answers with ours
+/// either way, so that the client can tell why it is disconnected. Returns whether the other
+/// messages of the client can be understood.
+pub fn handshake_with_client(stream: &mut (impl Read + Write)) -> bool {
+    let handshake = Handshake::recv(stream);
+    let _ = Handshake::current().send(stream);
+    matches!(handshake, Some(handshake) if handshake.is_compatible())
+}
+
 #[derive(PartialEq, Eq, Serialize, Deserialize, Hash)]
 pub struct Session {
     // Unique ID for this session
@@ -28,8 +140,71 @@ pub struct Session {
     alias: String,
 }
 
+/// What a running session tells about itself when listing sessions.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SessionInfo {
+    pub name: String,
+    /// When the session was started, in seconds since the Unix epoch
+    pub created: u64,
+    pub connected_clients: usize,
+    /// The names of the tabs, in the order they are shown
+    pub tabs: Vec<String>,
+    /// The version of zellij running the session
+    pub version: String,
+    /// Whether this zellij can talk to the session. Only the name, version and creation time
+    /// of incompatible sessions are known.
+    pub compatible: bool,
+    /// Whether the session answered when asked about itself, which incompatible sessions are
+    /// not. Only the name and creation time of unreachable sessions are known.
+    pub reachable: bool,
+}
+
+/// The tabs and panes of a running session.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SessionTree {
+    pub name: String,
+    /// The tabs, in the order they are shown
+    pub tabs: Vec<TabNode>,
+}
+
+/// A tab of a [`SessionTree`].
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TabNode {
+    pub position: usize,
+    pub name: String,
+    pub focused: bool,
+    pub panes: Vec<PaneNode>,
+}
+
+/// A pane of a [`TabNode`], and what runs in it.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PaneNode {
+    /// The id of the pane, unique among the panes of its [`PaneKind`]
+    pub id: u32,
+    pub kind: PaneKind,
+    /// The path of the plugin, if this is a plugin pane
+    pub plugin: Option<PathBuf>,
+    pub x: usize,
+    pub y: usize,
+    pub columns: usize,
+    pub rows: usize,
+    pub selectable: bool,
+    pub focused: bool,
+    pub title: Option<String>,
+    pub cwd: Option<PathBuf>,
+    /// The command running in the foreground of a terminal pane, and its arguments
+    pub command: Vec<String>,
+}
+
+/// What a [`PaneNode`] shows: terminal and plugin panes are numbered separately.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaneKind {
+    Terminal,
+    Plugin,
+}
+
 // How do we want to connect to a session?
-#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ClientType {
     Reader,
     Writer,
@@ -57,18 +232,36 @@ pub enum ClientToServerMsg {
     DisconnectFromSession,*/
     TerminalResize(PositionAndSize),
     NewClient(ClientAttributes, Box<CliArgs>, Box<Options>),
-    AttachClient(ClientAttributes, bool),
+    AttachClient(ClientAttributes, bool, ClientType),
     Action(Action),
+    QuerySessionInfo,
+    QuerySessionTree,
+    // The contents of a pane (the focused one if no id is given), with its scrollback and
+    // styles if asked for
+    CapturePane(Option<u32>, bool, bool),
+    // Bytes to write to the pane with the given id, whether it is focused or not
+    WriteToPane(u32, Vec<u8>),
+    // Save the tabs of the session as a layout, answered with whether it worked
+    DumpLayout(PathBuf),
+    // Give the session a new name, answered with whether it worked
+    RenameSession(String),
+    KillSession,
     ClientExited,
 }
 
 // Types of messages sent from the server to the client
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum ServerToClientMsg {
-    /*// Info about a particular session
-    SessionInfo(Session),
-    // A list of sessions
+    /*// A list of sessions
     SessionList(HashSet<Session>),*/
+    // Info about a particular session
+    SessionInfo(SessionInfo),
+    // The tabs and panes of the session
+    SessionTree(SessionTree),
+    // The contents of a pane, if it was found
+    PaneContents(Option<String>),
+    // Whether an action asked for by a query worked, and why not if it did not
+    ActionResult(Result<(), String>),
     Render(String),
     UnblockInputThread,
     Exit(ExitReason),
@@ -78,8 +271,11 @@ pub enum ServerToClientMsg {
 pub enum ExitReason {
     Normal,
     ForceDetached,
-    CannotAttach,
+    // The session runs another version of zellij (if it told which)
+    IncompatibleVersion(Option<String>),
     Error(String),
+    // The session runs a version of zellij that lacks some of the capabilities of ours
+    MissingCapabilities(String, Vec<String>),
 }
 
 impl Display for ExitReason {
@@ -88,11 +284,25 @@ impl Display for ExitReason {
             Self::Normal => write!(f, "Bye from Zellij!"),
             Self::ForceDetached => write!(
                 f,
-                "Session was detach from this client (possibly because another client connected)"
+                "Session was detached from this client (possibly because another client connected with --force)"
             ),
-            Self::CannotAttach => write!(
+            Self::IncompatibleVersion(Some(version)) => write!(
                 f,
-                "Session attached to another client. Use --force flag to force connect."
+                "The session runs zellij {}, which cannot talk to this zellij ({}). Use zellij {} to attach to it.",
+                version, VERSION, version
+            ),
+            Self::IncompatibleVersion(None) => write!(
+                f,
+                "The session runs another version of zellij, which cannot talk to this zellij ({}).",
+                VERSION
+            ),
+            Self::MissingCapabilities(version, capabilities) => write!(
+                f,
+                "The session runs zellij {}, which lacks the capabilities {} of this zellij ({}). Use zellij {} to attach to it.",
+                version,
+                capabilities.join(", "),
+                VERSION,
+                version
             ),
             Self::Error(e) => write!(f, "Error occured in server:\n{}", e),
         }
@@ -115,10 +325,12 @@ impl<T: Serialize> IpcSenderWithContext<T> {
     }
 
     /// Sends an event, along with the current [`ErrorContext`], on this [`IpcSenderWithContext`]'s socket.
-    pub fn send(&mut self, msg: T) {
+    /// Fails if the other end is gone.
+    pub fn send(&mut self, msg: T) -> io::Result<()> {
         let err_ctx = get_current_ctx();
-        bincode::serialize_into(&mut self.sender, &(msg, err_ctx)).unwrap();
-        self.sender.flush().unwrap();
+        bincode::serialize_into(&mut self.sender, &(msg, err_ctx))
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+        self.sender.flush()
     }
 
     /// Returns an [`IpcReceiverWithContext`] with the same socket as this sender.
@@ -152,8 +364,9 @@ where
     }
 
     /// Receives an event, along with the current [`ErrorContext`], on this [`IpcReceiverWithContext`]'s socket.
-    pub fn recv(&mut self) -> (T, ErrorContext) {
-        bincode::deserialize_from(&mut self.receiver).unwrap()
+    /// Fails if the other end is gone.
+    pub fn recv(&mut self) -> Result<(T, ErrorContext), bincode::Error> {
+        bincode::deserialize_from(&mut self.receiver)
     }
 
     /// Returns an [`IpcSenderWithContext`] with the same socket as this receiver.
@@ -164,3 +377,7 @@ where
         IpcSenderWithContext::new(socket)
     }
 }
+
+#[cfg(test)]
+#[path = "./unit/ipc_test.rs"]
+mod ipc_test;
@@ -14,6 +14,7 @@ pub use interprocess;
 pub use libc;
 pub use nix;
 pub use serde;
+pub use serde_json;
 pub use serde_yaml;
 pub use signal_hook;
 pub use structopt;
@@ -4,6 +4,7 @@ use crate::consts::{
 };
 use directories_next::BaseDirs;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::io::Write;
 use std::{path::Path, path::PathBuf};
 use structopt::StructOpt;
@@ -49,6 +50,14 @@ pub fn get_default_data_dir() -> PathBuf {
     .unwrap_or_else(xdg_data_dir)
 }
 
+/// The layout file the session `session_name` is periodically saved to, so that it can be
+/// resurrected with `zellij attach --resurrect`.
+pub fn get_saved_session_path(data_dir: &Path, session_name: &str) -> PathBuf {
+    data_dir
+        .join("sessions")
+        .join(format!("{}.yaml", session_name))
+}
+
 pub fn xdg_config_dir() -> PathBuf {
     ZELLIJ_PROJ_DIR.config_dir().to_owned()
 }
@@ -94,6 +103,10 @@ pub struct Setup {
     /// currently used directories
     #[structopt(long)]
     pub check: bool,
+    /// Checks a layout file for errors, and whether it fits
+    /// the current terminal
+    #[structopt(long, parse(from_os_str))]
+    pub check_layout: Option<PathBuf>,
 
     #[structopt(long)]
     pub generate_completion: Option<String>,
@@ -116,6 +129,11 @@ impl Setup {
             std::process::exit(0);
         }
 
+        if let Some(layout) = &self.check_layout {
+            let layout_is_valid = Setup::check_layout(layout, &opts.layout_variables())?;
+            std::process::exit(if layout_is_valid { 0 } else { 1 });
+        }
+
         if let Some(shell) = &self.generate_completion {
             Self::generate_completion(shell.into());
             std::process::exit(0);
@@ -185,6 +203,57 @@ impl Setup {
 
         Ok(())
     }
+    /// Prints whether the layout at `layout_path` is well defined, returning `false` if not.
+    pub fn check_layout(
+        layout_path: &Path,
+        variables: &HashMap<String, String>,
+    ) -> std::io::Result<bool> {
+        use crate::input::layout::SessionLayout;
+        use crate::pane_size::PositionAndSize;
+
+        let mut message = String::new();
+        message.push_str(&format!("[LAYOUT FILE]: {:?}\n", layout_path));
+        let layout_is_valid = match SessionLayout::new(layout_path, variables) {
+            Ok(layout) => {
+                message.push_str(&"[LAYOUT FILE]: Well defined.\n");
+                match termion::terminal_size() {
+                    Ok((columns, rows)) => {
+                        let screen_size = PositionAndSize {
+                            columns: columns as usize,
+                            rows: rows as usize,
+                            ..Default::default()
+                        };
+                        match layout.check_fits(&screen_size) {
+                            Ok(_) => {
+                                message.push_str(&format!(
+                                    "[SCREEN]: Fits the current terminal ({}x{}).\n",
+                                    columns, rows
+                                ));
+                                true
+                            }
+                            Err(e) => {
+                                message.push_str(&format!(
+                                    "[LAYOUT ERROR]: {} in the current terminal ({}x{})\n",
+                                    e, columns, rows
+                                ));
+                                false
+                            }
+                        }
+                    }
+                    Err(_) => {
+                        message.push_str(&"[SCREEN]: Not a terminal, sizes were not checked\n");
+                        true
+                    }
+                }
+            }
+            Err(e) => {
+                message.push_str(&format!("[LAYOUT ERROR]: {}\n", e));
+                false
+            }
+        };
+        std::io::stdout().write_all(message.as_bytes())?;
+        Ok(layout_is_valid)
+    }
     fn generate_completion(shell: String) {
         let shell = match shell.as_ref() {
             "bash" => structopt::clap::Shell::Bash,
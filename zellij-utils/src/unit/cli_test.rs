@@ -0,0 +1,74 @@
+use super::*;
+
+/// Parses `zellij action <args>`, into the session it is sent to and the actions it sends.
+fn parse_action(args: &[&str]) -> Result<(Option<String>, Vec<Action>), structopt::clap::Error> {
+    let args = ["zellij", "action"].iter().chain(args);
+    match CliArgs::from_iter_safe(args)?.command {
+        Some(Command::Action { session, action }) => Ok((session, action.actions())),
+        command => panic!("expected an action, got {:?}", command),
+    }
+}
+
+#[test]
+fn an_action_without_arguments_is_sent_as_is() {
+    let (session, actions) = parse_action(&["break-pane"]).unwrap();
+    assert_eq!(session, None);
+    assert_eq!(actions, vec![Action::BreakPane]);
+}
+
+#[test]
+fn an_action_is_sent_to_the_given_session() {
+    let (session, actions) = parse_action(&["--session", "work", "move-focus", "left"]).unwrap();
+    assert_eq!(session, Some(String::from("work")));
+    assert_eq!(actions, vec![Action::MoveFocus(Direction::Left)]);
+}
+
+#[test]
+fn the_arguments_of_an_action_are_parsed() {
+    let (_, actions) = parse_action(&["resize-pane", "--width", "40", "--height", "30%"]).unwrap();
+    assert_eq!(
+        actions,
+        vec![
+            Action::SetPaneWidth(PaneSize::Fixed(40)),
+            Action::SetPaneHeight(PaneSize::Percent(30)),
+        ]
+    );
+    let (_, actions) = parse_action(&["switch-mode", "locked"]).unwrap();
+    assert_eq!(actions, vec![Action::SwitchToMode(InputMode::Locked)]);
+    let (_, actions) = parse_action(&["rotate-panes", "--backwards"]).unwrap();
+    assert_eq!(actions, vec![Action::RotatePanesBackwards]);
+    let (_, actions) = parse_action(&["select-layout", "tiled"]).unwrap();
+    assert_eq!(actions, vec![Action::SelectLayout(LayoutPreset::Tiled)]);
+}
+
+#[test]
+fn written_characters_are_unescaped() {
+    let (_, actions) = parse_action(&["write-chars", "ls\\n"]).unwrap();
+    assert_eq!(actions, vec![Action::Write(b"ls\n".to_vec())]);
+    assert_eq!(unescape("a\\tb\\\\n\\x"), "a\tb\\n\\x");
+}
+
+#[test]
+fn renaming_a_tab_clears_its_name_first() {
+    let (_, actions) = parse_action(&["rename-tab", "logs"]).unwrap();
+    assert_eq!(
+        actions,
+        vec![
+            Action::TabNameInput(vec![0]),
+            Action::TabNameInput(b"logs".to_vec()),
+        ]
+    );
+}
+
+#[test]
+fn an_unknown_action_is_refused() {
+    assert!(parse_action(&["fly-away"]).is_err());
+}
+
+#[test]
+fn invalid_arguments_of_an_action_are_refused() {
+    assert!(parse_action(&["move-focus", "sideways"]).is_err());
+    assert!(parse_action(&["switch-mode", "nonsense"]).is_err());
+    assert!(parse_action(&["resize-pane", "--width", "120%"]).is_err());
+    assert!(parse_action(&["go-to-tab"]).is_err());
+}
@@ -0,0 +1,113 @@
+use super::*;
+use std::net::Shutdown;
+use std::os::unix::net::UnixStream;
+use std::thread;
+
+fn handshake_of_version(protocol_version: u32, version: &str) -> Handshake {
+    Handshake {
+        protocol_version,
+        version: version.to_string(),
+        ..Handshake::current()
+    }
+}
+
+#[test]
+fn handshake_round_trip() {
+    let mut bytes = Vec::new();
+    Handshake::current().send(&mut bytes).unwrap();
+    assert_eq!(Handshake::recv(&mut &bytes[..]), Some(Handshake::current()));
+}
+
+#[test]
+fn client_and_server_of_the_same_version_understand_each_other() {
+    let (mut client, mut server) = UnixStream::pair().unwrap();
+    let server = thread::spawn(move || handshake_with_client(&mut server));
+    assert!(handshake_with_server(&mut client).is_ok());
+    assert!(server.join().unwrap());
+}
+
+#[test]
+fn client_is_told_the_version_of_an_incompatible_server() {
+    let (mut client, mut server) = UnixStream::pair().unwrap();
+    let server = thread::spawn(move || {
+        let client_handshake = Handshake::recv(&mut server);
+        handshake_of_version(IPC_PROTOCOL_VERSION + 1, "100.0.0")
+            .send(&mut server)
+            .unwrap();
+        client_handshake
+    });
+    assert!(matches!(
+        handshake_with_server(&mut client),
+        Err(ExitReason::IncompatibleVersion(Some(version))) if version == "100.0.0"
+    ));
+    assert_eq!(server.join().unwrap(), Some(Handshake::current()));
+}
+
+#[test]
+fn server_refuses_an_incompatible_client_but_answers_it() {
+    let (mut client, mut server) = UnixStream::pair().unwrap();
+    let server = thread::spawn(move || handshake_with_client(&mut server));
+    handshake_of_version(IPC_PROTOCOL_VERSION + 1, "100.0.0")
+        .send(&mut client)
+        .unwrap();
+    assert!(!server.join().unwrap());
+    assert_eq!(Handshake::recv(&mut client), Some(Handshake::current()));
+}
+
+#[test]
+fn server_refuses_a_client_without_handshake() {
+    let (mut client, mut server) = UnixStream::pair().unwrap();
+    let server = thread::spawn(move || handshake_with_client(&mut server));
+    // what a client of a version without handshake would start with
+    client.write_all(b"not a handshake").unwrap();
+    client.shutdown(Shutdown::Write).unwrap();
+    assert!(!server.join().unwrap());
+}
+
+#[test]
+fn client_is_told_the_capabilities_the_server_lacks() {
+    let (mut client, mut server) = UnixStream::pair().unwrap();
+    let server = thread::spawn(move || {
+        let client_handshake = Handshake::recv(&mut server);
+        Handshake {
+            version: "100.0.0".to_string(),
+            capabilities: vec!["attach-read-only".to_string()],
+            ..Handshake::current()
+        }
+        .send(&mut server)
+        .unwrap();
+        client_handshake
+    });
+    match handshake_with_server(&mut client) {
+        Err(ExitReason::MissingCapabilities(version, missing_capabilities)) => {
+            assert_eq!(version, "100.0.0");
+            assert!(missing_capabilities.contains(&"capture-pane".to_string()));
+            assert!(!missing_capabilities.contains(&"attach-read-only".to_string()));
+        }
+        other => panic!("expected missing capabilities, got {:?}", other),
+    }
+    assert_eq!(server.join().unwrap(), Some(Handshake::current()));
+}
+
+#[test]
+fn server_refuses_a_client_that_lacks_capabilities() {
+    let (mut client, mut server) = UnixStream::pair().unwrap();
+    let server = thread::spawn(move || handshake_with_client(&mut server));
+    Handshake {
+        capabilities: Vec::new(),
+        ..Handshake::current()
+    }
+    .send(&mut client)
+    .unwrap();
+    assert!(!server.join().unwrap());
+    assert_eq!(Handshake::recv(&mut client), Some(Handshake::current()));
+}
+
+#[test]
+fn extra_capabilities_are_understood() {
+    let mut handshake = Handshake::current();
+    handshake
+        .capabilities
+        .push("from-a-later-version".to_string());
+    assert!(handshake.is_compatible());
+}